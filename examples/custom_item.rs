@@ -17,6 +17,10 @@ impl SkimItem for MyItem {
             ItemPreview::Text(format!("hello:\n{}", self.inner))
         }
     }
+
+    fn annotation(&self) -> Option<Cow<str>> {
+        Some(Cow::Owned(format!("{} chars", self.inner.len())))
+    }
 }
 
 pub fn main() {
@@ -0,0 +1,50 @@
+extern crate skim;
+use skim::prelude::*;
+
+struct GroupedItem {
+    group: &'static str,
+    inner: String,
+}
+
+impl SkimItem for GroupedItem {
+    fn text(&self) -> Cow<str> {
+        Cow::Borrowed(&self.inner)
+    }
+
+    fn group(&self) -> Option<Cow<str>> {
+        Some(Cow::Borrowed(self.group))
+    }
+}
+
+pub fn main() {
+    // "rank": the group of the best match comes first, "index": the groups stay in their order
+    let options = SkimOptionsBuilder::default()
+        .height(Some("50%"))
+        .groups(Some("index"))
+        .build()
+        .unwrap();
+
+    let (tx_item, rx_item): (SkimItemSender, SkimItemReceiver) = unbounded();
+    for (group, inner) in [
+        ("buffers", "main.rs"),
+        ("buffers", "lib.rs"),
+        ("files", "src/main.rs"),
+        ("files", "src/lib.rs"),
+        ("files", "Cargo.toml"),
+        ("symbols", "fn main"),
+    ] {
+        let _ = tx_item.send(Arc::new(GroupedItem {
+            group,
+            inner: inner.to_string(),
+        }));
+    }
+    drop(tx_item); // so that skim could know when to stop waiting for more items.
+
+    let selected_items = Skim::run_with(&options, Some(rx_item))
+        .map(|out| out.selected_items)
+        .unwrap_or_default();
+
+    for item in selected_items.iter() {
+        println!("{}", item.output());
+    }
+}
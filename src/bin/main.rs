@@ -9,6 +9,7 @@ use derive_builder::Builder;
 use std::env;
 use std::fs::File;
 use std::io::{BufRead, BufReader, BufWriter, IsTerminal, Write};
+use std::path::{Path, PathBuf};
 
 use clap::{crate_version, App, Arg, ArgMatches};
 use skim::prelude::*;
@@ -28,6 +29,8 @@ Usage: sk [options]
   Search
     --tac                reverse the order of search result
     --no-sort            Do not sort the result
+    --top-k=K            Only keep the best K items ordered, deeper pages are
+                         ordered on demand. [auto|NUM] (auto: screen height + 100)
     -t, --tiebreak [score,begin,end,-score,length...]
 
                          comma seperated criteria
@@ -47,11 +50,20 @@ Usage: sk [options]
     -m, --multi          Enable Multiple Selection
     --no-multi           Disable Multiple Selection
     --no-mouse           Disable mouse events
+    --cycle              Enable cyclic scroll
+    --jump-labels=CHARS  Label characters for jump and jump-accept
+                         (default: abcdefghijklmnopqrstuvwxyz)
     -c, --cmd ag         command to invoke dynamically
     -i, --interactive    Start skim in interactive(command) mode
-    --color [BASE][,COLOR:ANSI]
+    --color [BASE][,COLOR:ANSI[:ATTR]]
                          change color theme
+    --color-file=FILE    read the color theme from the file
+    --theme=NAME         read the color theme from the themes directory
+                         ($XDG_CONFIG_HOME/sk/themes)
+    --no-bold            Do not use bold text
     --no-hscroll         Disable horizontal scroll
+    --hscroll-off=COL    Number of columns to keep visible around the match
+                         when the line is shifted (default: 10)
     --keep-right         Keep the right end of the line visible on overflow
     --skip-to-pattern    Line starts with the start of matched pattern
     --no-clear-if-empty  Do not clear previous items if command returns empty result
@@ -66,15 +78,49 @@ Usage: sk [options]
                          (default: 10)
     --margin=MARGIN      Screen Margin (TRBL / TB,RL / T,RL,B / T,R,B,L)
                          e.g. (sk --margin 1,10%)
+    --border[=STYLE]     Draw border around the finder
+                         [rounded|sharp|bold|double|horizontal|none]
+                         (default: rounded)
+    --border-label=LABEL Label to print on the border
+    --border-label-pos=N[:top|bottom]
+                         Position of the border label, counted from the left
+                         (from the right if negative, centered if 0)
+    --list-border[=STYLE]
+    --list-label=LABEL
+    --list-label-pos=N[:top|bottom]
+                         Border and label around the list
+    --header-border[=STYLE]
+    --header-label=LABEL
+    --header-label-pos=N[:top|bottom]
+                         Border and label around the header
     -p, --prompt '> '    prompt string for query mode
     --cmd-prompt '> '    prompt string for command mode
+    --ghost=TEXT         placeholder shown while the query is empty
+    --cmd-ghost=TEXT     placeholder shown while the command query is empty
 
   Display
     --ansi               parse ANSI color codes for input strings
     --tabstop=SPACES     Number of spaces for a tab character (default: 8)
-    --inline-info        Display info next to query
+    --scrollbar[=C1[C2]] Display scrollbars for the list (C1) and the preview
+                         window (C2) (default: '│')
+    --multi-line=MAX     Maximum number of lines displayed for an item
+                         containing newlines, e.g. with --read0
+                         (default: 0, no limit; 1 for the first line only)
+    --tabular[=MAX]      Align the fields (split by --delimiter) in columns,
+                         truncate columns wider than MAX (default: 40, 0 for
+                         no limit)
+    --tree[=SEP]         Display the items as a tree of paths split by SEP
+                         (default: '/'), fold directories with left/right
+    --inline-info        Display info next to query (same as --info=inline)
+    --info=STYLE         Info style: default|inline|inline-right|right-of-prompt|hidden
+    --info-format=FMT    Template of the info, e.g. '{matched}/{total} [{selected}]'
+                         ({matched} {total} {processed} {percent} {selected}
+                          {mode} {current} {hscroll})
     --header=STR         Display STR next to info
     --header-lines=N     The first N lines of the input are treated as header
+    --pointer=STR        Pointer to the current line (default: '>')
+    --marker=STR         Multi-select marker (default: '>')
+    --unmarked=STR       Placeholder of the items not selected (default: ' ')
 
   History
     --history=FILE       History file
@@ -86,10 +132,21 @@ Usage: sk [options]
     --preview=COMMAND    command to preview current highlighted line ({})
                          We can specify the fields. e.g. ({1}, {..3}, {0..})
     --preview-window=OPT Preview window layout (default: right:50%)
-                         [up|down|left|right][:SIZE[%]][:hidden][:+SCROLL[-OFFSET]]
+                         [up|down|left|right][:SIZE[%]][:hidden][:follow][:+SCROLL[-OFFSET]]
+    --preview-interval=DURATION
+                         Re-run the preview command periodically, e.g. 2s, 500ms
+    --preview-cache-size=N
+                         Cache the last N outputs of the preview command (default: 0)
+    --preview-border=STYLE
+    --preview-label=LABEL
+    --preview-label-pos=N[:top|bottom]
+                         Border and label around the preview window
+                         (default: a separator line)
 
   Scripting
     -q, --query \"\"       specify the initial query
+    --input-file=FILE    Read input from FILE instead of stdin
+                         (regular files are memory-mapped, as is redirected stdin)
     --cmd-query \"\"       specify the initial query for interactive mode
     --expect KEYS        comma seperated keys that can be used to complete skim
     --read0              Read input delimited by ASCII NUL(\\0) characters
@@ -122,15 +179,7 @@ Usage: sk [options]
   Reserved (not used for now)
     --extended
     --literal
-    --cycle
-    --hscroll-off=COL
     --filepath-word
-    --jump-labels=CHARS
-    --border
-    --no-bold
-    --info
-    --pointer
-    --marker
     --phony
 ";
 
@@ -180,6 +229,8 @@ fn real_main() -> Result<i32, std::io::Error> {
         .arg(Arg::with_name("no-multi").long("no-multi").multiple(true))
         .arg(Arg::with_name("prompt").long("prompt").short('p').multiple(true).takes_value(true).default_value("> "))
         .arg(Arg::with_name("cmd-prompt").long("cmd-prompt").multiple(true).takes_value(true).default_value("c> "))
+        .arg(Arg::with_name("ghost").long("ghost").multiple(true).takes_value(true))
+        .arg(Arg::with_name("cmd-ghost").long("cmd-ghost").multiple(true).takes_value(true))
         .arg(Arg::with_name("expect").long("expect").multiple(true).takes_value(true))
         .arg(Arg::with_name("tac").long("tac").multiple(true))
         .arg(Arg::with_name("tiebreak").long("tiebreak").short('t').multiple(true).takes_value(true).default_value("length,index"))
@@ -196,6 +247,8 @@ fn real_main() -> Result<i32, std::io::Error> {
         .arg(Arg::with_name("with-nth").long("with-nth").multiple(true).takes_value(true))
         .arg(Arg::with_name("replstr").short('I').multiple(true).takes_value(true))
         .arg(Arg::with_name("color").long("color").multiple(true).takes_value(true))
+        .arg(Arg::with_name("color-file").long("color-file").multiple(true).takes_value(true))
+        .arg(Arg::with_name("theme").long("theme").multiple(true).takes_value(true))
         .arg(Arg::with_name("margin").long("margin").multiple(true).takes_value(true).default_value("0,0,0,0"))
         .arg(Arg::with_name("min-height").long("min-height").multiple(true).takes_value(true).default_value("10"))
         .arg(Arg::with_name("height").long("height").multiple(true).takes_value(true).default_value("100%"))
@@ -205,6 +258,8 @@ fn real_main() -> Result<i32, std::io::Error> {
         .arg(Arg::with_name("no-mouse").long("no-mouse").multiple(true))
         .arg(Arg::with_name("preview").long("preview").multiple(true).takes_value(true))
         .arg(Arg::with_name("preview-window").long("preview-window").multiple(true).takes_value(true).default_value("right:50%"))
+        .arg(Arg::with_name("preview-interval").long("preview-interval").multiple(true).takes_value(true))
+        .arg(Arg::with_name("preview-cache-size").long("preview-cache-size").multiple(true).takes_value(true).default_value("0"))
         .arg(Arg::with_name("reverse").long("reverse").multiple(true))
         .arg(Arg::with_name("algo").long("algo").multiple(false).takes_value(true).default_value("skim_v2"))
         .arg(Arg::with_name("case").long("case").multiple(true).takes_value(true).default_value("smart"))
@@ -214,12 +269,32 @@ fn real_main() -> Result<i32, std::io::Error> {
         .arg(Arg::with_name("hscroll-off").long("hscroll-off").multiple(true).takes_value(true).default_value("10"))
         .arg(Arg::with_name("filepath-word").long("filepath-word").multiple(true))
         .arg(Arg::with_name("jump-labels").long("jump-labels").multiple(true).takes_value(true).default_value("abcdefghijklmnopqrstuvwxyz"))
-        .arg(Arg::with_name("border").long("border").multiple(true))
+        .arg(Arg::with_name("border").long("border").multiple(true).takes_value(true).min_values(0).require_equals(true).default_missing_value("rounded"))
+        .arg(Arg::with_name("list-border").long("list-border").multiple(true).takes_value(true).min_values(0).require_equals(true).default_missing_value("rounded"))
+        .arg(Arg::with_name("header-border").long("header-border").multiple(true).takes_value(true).min_values(0).require_equals(true).default_missing_value("rounded"))
+        .arg(Arg::with_name("preview-border").long("preview-border").multiple(true).takes_value(true))
+        .arg(Arg::with_name("border-label").long("border-label").multiple(true).takes_value(true))
+        .arg(Arg::with_name("border-label-pos").long("border-label-pos").multiple(true).takes_value(true).allow_hyphen_values(true))
+        .arg(Arg::with_name("list-label").long("list-label").multiple(true).takes_value(true))
+        .arg(Arg::with_name("list-label-pos").long("list-label-pos").multiple(true).takes_value(true).allow_hyphen_values(true))
+        .arg(Arg::with_name("header-label").long("header-label").multiple(true).takes_value(true))
+        .arg(Arg::with_name("header-label-pos").long("header-label-pos").multiple(true).takes_value(true).allow_hyphen_values(true))
+        .arg(Arg::with_name("preview-label").long("preview-label").multiple(true).takes_value(true))
+        .arg(Arg::with_name("preview-label-pos").long("preview-label-pos").multiple(true).takes_value(true).allow_hyphen_values(true))
         .arg(Arg::with_name("inline-info").long("inline-info").multiple(true))
+        .arg(Arg::with_name("info").long("info").multiple(true).takes_value(true))
+        .arg(Arg::with_name("info-format").long("info-format").multiple(true).takes_value(true))
         .arg(Arg::with_name("header").long("header").multiple(true).takes_value(true).default_value(""))
         .arg(Arg::with_name("header-lines").long("header-lines").multiple(true).takes_value(true).default_value("0"))
+        .arg(Arg::with_name("scrollbar").long("scrollbar").multiple(true).takes_value(true).min_values(0).require_equals(true).default_missing_value("│"))
+        .arg(Arg::with_name("multi-line").long("multi-line").multiple(true).takes_value(true))
+        .arg(Arg::with_name("tree").long("tree").multiple(true).takes_value(true).min_values(0).require_equals(true).default_missing_value("/"))
+        .arg(Arg::with_name("tabular").long("tabular").multiple(true).takes_value(true).min_values(0).require_equals(true).default_missing_value("40"))
         .arg(Arg::with_name("tabstop").long("tabstop").multiple(true).takes_value(true).default_value("8"))
         .arg(Arg::with_name("no-bold").long("no-bold").multiple(true))
+        .arg(Arg::with_name("pointer").long("pointer").multiple(true).takes_value(true))
+        .arg(Arg::with_name("marker").long("marker").multiple(true).takes_value(true))
+        .arg(Arg::with_name("unmarked").long("unmarked").multiple(true).takes_value(true))
         .arg(Arg::with_name("history").long("history").multiple(true).takes_value(true))
         .arg(Arg::with_name("cmd-history").long("cmd-history").multiple(true).takes_value(true))
         .arg(Arg::with_name("history-size").long("history-size").multiple(true).takes_value(true).default_value("1000"))
@@ -228,10 +303,12 @@ fn real_main() -> Result<i32, std::io::Error> {
         .arg(Arg::with_name("print-cmd").long("print-cmd").multiple(true))
         .arg(Arg::with_name("print-score").long("print-score").multiple(true))
         .arg(Arg::with_name("read0").long("read0").multiple(true))
+        .arg(Arg::with_name("input-file").long("input-file").multiple(true).takes_value(true))
         .arg(Arg::with_name("print0").long("print0").multiple(true))
         .arg(Arg::with_name("sync").long("sync").multiple(true))
         .arg(Arg::with_name("extended").long("extended").short('x').multiple(true))
         .arg(Arg::with_name("no-sort").long("no-sort").multiple(true))
+        .arg(Arg::with_name("top-k").long("top-k").multiple(true).takes_value(true))
         .arg(Arg::with_name("select-1").long("select-1").short('1').multiple(true))
         .arg(Arg::with_name("exit-0").long("exit-0").short('0').multiple(true))
         .arg(Arg::with_name("filter").long("filter").short('f').takes_value(true).multiple(true))
@@ -254,6 +331,47 @@ fn real_main() -> Result<i32, std::io::Error> {
     //------------------------------------------------------------------------------
     let mut options = parse_options(&opts);
 
+    let color = match color_spec(&opts, config_dir().as_deref()) {
+        Ok(color) => color,
+        Err(err) => {
+            eprintln!("sk: {}", err);
+            return Ok(2);
+        }
+    };
+    options.color = color.as_deref();
+
+    if let Some(Err(err)) = options.color.map(ColorTheme::parse) {
+        eprintln!("sk: invalid --color: {}", err);
+        return Ok(2);
+    }
+
+    for (name, style) in [
+        ("border", options.border),
+        ("list-border", options.list_border),
+        ("header-border", options.header_border),
+        ("preview-border", options.preview_border),
+    ] {
+        if let Some(Err(err)) = style.map(BorderStyle::parse) {
+            eprintln!("sk: invalid --{}: {}", name, err);
+            return Ok(2);
+        }
+    }
+
+    if let Err(err) = options.validate() {
+        eprintln!("sk: {}", err);
+        return Ok(2);
+    }
+
+    if let Some(size) = opts.values_of("preview-cache-size").and_then(|vals| vals.last()) {
+        match size.parse::<usize>() {
+            Ok(size) => options.preview_cache_size = size,
+            Err(_) => {
+                eprintln!("sk: invalid --preview-cache-size: {}", size);
+                return Ok(2);
+            }
+        }
+    }
+
     let preview_window_joined = opts.values_of("preview-window").map(|x| x.collect::<Vec<_>>().join(":"));
     options.preview_window = preview_window_joined.as_deref();
 
@@ -320,8 +438,19 @@ fn real_main() -> Result<i32, std::io::Error> {
     //------------------------------------------------------------------------------
     // read from pipe or command
 
-    let (rx_item, opt_ingest_handle) = if !std::io::stdin().is_terminal() {
-            let (rx_item, opt_ingest_handle) = cmd_collector.borrow().of_bufread(Box::new(BufReader::with_capacity(READ_BUFFER_CAPACITY, std::io::stdin())));
+    let (rx_item, opt_ingest_handle) = if let Some(path) = opts.values_of("input-file").and_then(|vals| vals.last()) {
+            let file = File::open(path).map_err(|err| {
+                eprintln!("sk: could not open input file {}: {}", path, err);
+                err
+            })?;
+            let (rx_item, opt_ingest_handle) = cmd_collector.borrow().of_file(file);
+            (Some(rx_item), opt_ingest_handle)
+        } else if !std::io::stdin().is_terminal() {
+            let (rx_item, opt_ingest_handle) = match stdin_as_file() {
+                // stdin redirected from a regular file could be memory-mapped
+                Some(file) => cmd_collector.borrow().of_file(file),
+                None => cmd_collector.borrow().of_bufread(Box::new(BufReader::with_capacity(READ_BUFFER_CAPACITY, std::io::stdin()))),
+            };
             (Some(rx_item),  opt_ingest_handle)
         } else {
          (None, None)
@@ -401,18 +530,20 @@ fn real_main() -> Result<i32, std::io::Error> {
 
 fn parse_options(options: &ArgMatches) -> SkimOptions<'_> {
     SkimOptionsBuilder::default()
-        .color(options.values_of("color").and_then(|vals| vals.last()))
         .min_height(options.values_of("min-height").and_then(|vals| vals.last()))
         .no_height(options.is_present("no-height"))
         .height(options.values_of("height").and_then(|vals| vals.last()))
         .margin(options.values_of("margin").and_then(|vals| vals.last()))
         .preview(options.values_of("preview").and_then(|vals| vals.last()))
+        .preview_interval(options.values_of("preview-interval").and_then(|vals| vals.last()))
         .cmd(options.values_of("cmd").and_then(|vals| vals.last()))
         .query(options.values_of("query").and_then(|vals| vals.last()))
         .cmd_query(options.values_of("cmd-query").and_then(|vals| vals.last()))
         .interactive(options.is_present("interactive"))
         .prompt(options.values_of("prompt").and_then(|vals| vals.last()))
         .cmd_prompt(options.values_of("cmd-prompt").and_then(|vals| vals.last()))
+        .ghost(options.values_of("ghost").and_then(|vals| vals.last()))
+        .cmd_ghost(options.values_of("cmd-ghost").and_then(|vals| vals.last()))
         .bind(
             options
                 .values_of("bind")
@@ -428,18 +559,29 @@ fn parse_options(options: &ArgMatches) -> SkimOptions<'_> {
         .layout(options.values_of("layout").and_then(|vals| vals.last()).unwrap_or(""))
         .reverse(options.is_present("reverse"))
         .no_hscroll(options.is_present("no-hscroll"))
+        .no_bold(options.is_present("no-bold"))
         .no_mouse(options.is_present("no-mouse"))
+        .cycle(options.is_present("cycle"))
+        .jump_labels(options.values_of("jump-labels").and_then(|vals| vals.last()))
         .no_clear(options.is_present("no-clear"))
         .no_clear_start(options.is_present("no-clear-start"))
         .tabstop(options.values_of("tabstop").and_then(|vals| vals.last()))
+        .hscroll_off(options.values_of("hscroll-off").and_then(|vals| vals.last()))
+        .multi_line(options.values_of("multi-line").and_then(|vals| vals.last()))
+        .tabular(options.values_of("tabular").and_then(|vals| vals.last()))
+        .tree(options.values_of("tree").and_then(|vals| vals.last()))
+        .scrollbar(options.values_of("scrollbar").and_then(|vals| vals.last()))
         .tiebreak(options.values_of("tiebreak").map(|x| x.collect::<Vec<_>>().join(",")))
         .tac(options.is_present("tac"))
         .nosort(options.is_present("no-sort"))
+        .top_k(options.values_of("top-k").and_then(|vals| vals.last()))
         .exact(options.is_present("exact"))
         .disabled(options.is_present("disabled"))
         .regex(options.is_present("regex"))
         .delimiter(options.values_of("delimiter").and_then(|vals| vals.last()))
         .inline_info(options.is_present("inline-info"))
+        .info(options.values_of("info").and_then(|vals| vals.last()))
+        .info_format(options.values_of("info-format").and_then(|vals| vals.last()))
         .header(options.values_of("header").and_then(|vals| vals.last()))
         .header_lines(
             options
@@ -448,6 +590,21 @@ fn parse_options(options: &ArgMatches) -> SkimOptions<'_> {
                 .map(|s| s.parse::<usize>().unwrap_or(0))
                 .unwrap_or(0),
         )
+        .border(options.values_of("border").and_then(|vals| vals.last()))
+        .border_label(options.values_of("border-label").and_then(|vals| vals.last()))
+        .border_label_pos(options.values_of("border-label-pos").and_then(|vals| vals.last()))
+        .list_border(options.values_of("list-border").and_then(|vals| vals.last()))
+        .list_label(options.values_of("list-label").and_then(|vals| vals.last()))
+        .list_label_pos(options.values_of("list-label-pos").and_then(|vals| vals.last()))
+        .header_border(options.values_of("header-border").and_then(|vals| vals.last()))
+        .header_label(options.values_of("header-label").and_then(|vals| vals.last()))
+        .header_label_pos(options.values_of("header-label-pos").and_then(|vals| vals.last()))
+        .preview_border(options.values_of("preview-border").and_then(|vals| vals.last()))
+        .preview_label(options.values_of("preview-label").and_then(|vals| vals.last()))
+        .preview_label_pos(options.values_of("preview-label-pos").and_then(|vals| vals.last()))
+        .pointer(options.values_of("pointer").and_then(|vals| vals.last()))
+        .marker(options.values_of("marker").and_then(|vals| vals.last()))
+        .unmarked(options.values_of("unmarked").and_then(|vals| vals.last()))
         .layout(options.values_of("layout").and_then(|vals| vals.last()).unwrap_or(""))
         .algorithm(FuzzyAlgorithm::of(&options.value_of("algo").unwrap()))
         .case(match options.value_of("case") {
@@ -470,6 +627,61 @@ fn parse_options(options: &ArgMatches) -> SkimOptions<'_> {
         .unwrap()
 }
 
+#[cfg(unix)]
+fn stdin_as_file() -> Option<File> {
+    use std::os::unix::io::AsFd;
+
+    let fd = std::io::stdin().as_fd().try_clone_to_owned().ok()?;
+    let file = File::from(fd);
+    file.metadata().ok().filter(|metadata| metadata.is_file()).map(|_| file)
+}
+
+#[cfg(not(unix))]
+fn stdin_as_file() -> Option<File> {
+    None
+}
+
+/// the color specs of the theme (`--theme`, in the themes directory of `config_dir`), the color
+/// file (`--color-file`) and `--color`, the later ones override the former ones
+fn color_spec(opts: &ArgMatches, config_dir: Option<&Path>) -> Result<Option<String>, String> {
+    let mut specs = Vec::new();
+
+    if let Some(name) = opts.values_of("theme").and_then(|vals| vals.last()) {
+        let path = config_dir
+            .map(|dir| themes_dir(dir).join(name))
+            .filter(|path| path.is_file())
+            .ok_or_else(|| format!("theme {} not found", name))?;
+        specs.push(read_color_file(&path)?);
+    }
+
+    if let Some(path) = opts.values_of("color-file").and_then(|vals| vals.last()) {
+        specs.push(read_color_file(Path::new(path))?);
+    }
+
+    if let Some(color) = opts.values_of("color").and_then(|vals| vals.last()) {
+        specs.push(color.to_string());
+    }
+
+    Ok(if specs.is_empty() { None } else { Some(specs.join(",")) })
+}
+
+fn read_color_file(path: &Path) -> Result<String, String> {
+    ColorTheme::read_spec(path).map_err(|err| format!("could not read color file {}: {}", path.display(), err))
+}
+
+/// `$XDG_CONFIG_HOME`, or `~/.config`
+fn config_dir() -> Option<PathBuf> {
+    env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+}
+
+/// the directory of the themes in the config directory, `sk/themes`
+fn themes_dir(config_dir: &Path) -> PathBuf {
+    config_dir.join("sk").join("themes")
+}
+
 fn read_file_lines(filename: &str) -> Result<Vec<String>, std::io::Error> {
     let file = File::open(filename)?;
     let ret = BufReader::new(file).lines().collect();
@@ -550,7 +762,7 @@ pub fn filter(
 
     //------------------------------------------------------------------------------
     // start
-    let components_to_stop = Arc::new(AtomicUsize::new(0));
+    let components_to_stop = Arc::new(WaitGroup::new());
 
     let stream_of_item = source.unwrap_or_else(|| {
         let cmd_collector = options.cmd_collector.clone();
@@ -569,3 +781,44 @@ pub fn filter(
 
     Ok(if num_matched == 0 { 1 } else { 0 })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tuikit::prelude::Color;
+
+    #[test]
+    fn test_color_spec() {
+        let dir = env::temp_dir().join(format!("sk-test-color-spec-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("sk/themes")).unwrap();
+        std::fs::write(dir.join("sk/themes/mine"), "light\nhl:red\n").unwrap();
+        std::fs::write(dir.join("colors"), "# override the theme\nhl:blue\nfg:green\n").unwrap();
+        assert_eq!(themes_dir(&dir), dir.join("sk/themes"));
+
+        let color_file = dir.join("colors");
+        let opts = App::new("sk")
+            .arg(Arg::with_name("color").long("color").multiple(true).takes_value(true))
+            .arg(
+                Arg::with_name("color-file")
+                    .long("color-file")
+                    .multiple(true)
+                    .takes_value(true),
+            )
+            .arg(Arg::with_name("theme").long("theme").multiple(true).takes_value(true))
+            .get_matches_from(vec![
+                "sk",
+                "--color=fg:yellow",
+                &format!("--color-file={}", color_file.display()),
+                "--theme=mine",
+            ]);
+        let spec = color_spec(&opts, Some(&dir));
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        // theme < color file < --color
+        let spec = spec.unwrap().unwrap();
+        assert_eq!(spec, "light,hl:red,hl:blue,fg:green,fg:yellow");
+        let theme = ColorTheme::parse(&spec).unwrap();
+        assert_eq!(theme.matched().fg, Color::BLUE);
+        assert_eq!(theme.normal().fg, Color::YELLOW);
+    }
+}
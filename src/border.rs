@@ -0,0 +1,289 @@
+//! Borders around the finder and its areas (list, preview, header)
+//!
+//! `Win` of tuikit only draws sharp borders. The bordered widget here lets a `Win` reserve the
+//! space for the border (and translate mouse events), then draws the border with the chosen style
+//! and the label over it.
+use std::cmp::{max, min};
+
+use tuikit::prelude::*;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::theme::ColorTheme;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BorderStyle {
+    Rounded,
+    Sharp,
+    Bold,
+    Double,
+    /// only the top and bottom lines
+    Horizontal,
+}
+
+struct BorderGlyphs {
+    horizontal: char,
+    vertical: char,
+    top_left: char,
+    top_right: char,
+    bottom_left: char,
+    bottom_right: char,
+}
+
+impl BorderStyle {
+    /// parse the style, `None` for "none", an empty style is rounded
+    pub fn parse(style: &str) -> std::result::Result<Option<Self>, String> {
+        let style = match style {
+            "none" => None,
+            "" | "rounded" => Some(BorderStyle::Rounded),
+            "sharp" => Some(BorderStyle::Sharp),
+            "bold" => Some(BorderStyle::Bold),
+            "double" => Some(BorderStyle::Double),
+            "horizontal" => Some(BorderStyle::Horizontal),
+            _ => return Err(format!("unknown border style '{}'", style)),
+        };
+        Ok(style)
+    }
+
+    fn has_sides(self) -> bool {
+        self != BorderStyle::Horizontal
+    }
+
+    fn glyphs(self) -> BorderGlyphs {
+        let (horizontal, vertical, top_left, top_right, bottom_left, bottom_right) = match self {
+            BorderStyle::Rounded => ('─', '│', '╭', '╮', '╰', '╯'),
+            BorderStyle::Sharp | BorderStyle::Horizontal => ('─', '│', '┌', '┐', '└', '┘'),
+            BorderStyle::Bold => ('━', '┃', '┏', '┓', '┗', '┛'),
+            BorderStyle::Double => ('═', '║', '╔', '╗', '╚', '╝'),
+        };
+
+        BorderGlyphs {
+            horizontal,
+            vertical,
+            top_left,
+            top_right,
+            bottom_left,
+            bottom_right,
+        }
+    }
+}
+
+/// A label drawn on the border
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BorderLabel {
+    text: String,
+    // > 0: columns from the left, < 0: columns from the right, 0: centered
+    offset: i32,
+    bottom: bool,
+}
+
+impl BorderLabel {
+    /// `pos` is `[-]N[:top|:bottom]`, e.g. `2`, `-3:bottom` or `0` (centered)
+    pub fn new(text: &str, pos: Option<&str>) -> Self {
+        let mut offset = 0;
+        let mut bottom = false;
+
+        for part in pos.unwrap_or("").split(':') {
+            match part {
+                "top" => bottom = false,
+                "bottom" => bottom = true,
+                _ => offset = part.parse::<i32>().unwrap_or(offset),
+            }
+        }
+
+        Self {
+            text: text.to_string(),
+            offset,
+            bottom,
+        }
+    }
+
+    /// the start column of the label on a border line of `width`, and the width to be printed
+    fn position(&self, width: usize) -> (usize, usize) {
+        // leave the corners alone
+        let available = max(width, 2) - 2;
+        let text_width = min(self.text.width_cjk(), available);
+        let max_start = 1 + available - text_width;
+
+        let start = match self.offset {
+            0 => 1 + (available - text_width) / 2,
+            offset if offset > 0 => offset as usize,
+            offset => max(width as i32 + offset - text_width as i32, 1) as usize,
+        };
+
+        (min(max(start, 1), max_start), text_width)
+    }
+}
+
+/// The configuration of one border
+#[derive(Debug, Clone)]
+pub struct Border {
+    style: BorderStyle,
+    label: Option<BorderLabel>,
+    attr: Attr,
+    label_attr: Attr,
+}
+
+impl Border {
+    pub fn new(style: BorderStyle) -> Self {
+        Self {
+            style,
+            label: None,
+            attr: Attr::default(),
+            label_attr: Attr::default(),
+        }
+    }
+
+    /// `None` if no border is drawn, i.e. no `style`, it is "none" or unknown
+    pub fn from_options(
+        style: Option<&str>,
+        label: Option<&str>,
+        label_pos: Option<&str>,
+        theme: &ColorTheme,
+    ) -> Option<Self> {
+        let style = style.and_then(|style| BorderStyle::parse(style).ok().flatten())?;
+        Some(
+            Border::new(style)
+                .label(label.map(|label| BorderLabel::new(label, label_pos)))
+                .attr(theme.border())
+                .label_attr(theme.label()),
+        )
+    }
+
+    pub fn label(mut self, label: Option<BorderLabel>) -> Self {
+        self.label = label;
+        self
+    }
+
+    pub fn attr(mut self, attr: Attr) -> Self {
+        self.attr = attr;
+        self
+    }
+
+    pub fn label_attr(mut self, label_attr: Attr) -> Self {
+        self.label_attr = label_attr;
+        self
+    }
+
+    /// wrap the widget in a bordered `Win`, which could be further configured (basis, grow, ...)
+    pub fn wrap<'a, Message: 'a>(&self, widget: impl Widget<Message> + 'a) -> Win<'a, Message> {
+        let sides = self.style.has_sides();
+        let win = Win::new(widget)
+            .border_top(true)
+            .border_bottom(true)
+            .border_left(sides)
+            .border_right(sides);
+
+        Win::new(Bordered {
+            win,
+            border: self.clone(),
+        })
+    }
+}
+
+struct Bordered<'a, Message> {
+    win: Win<'a, Message>,
+    border: Border,
+}
+
+impl<'a, Message> Bordered<'a, Message> {
+    fn draw_border(&self, canvas: &mut dyn Canvas) -> DrawResult<()> {
+        let (width, height) = canvas.size()?;
+        if width < 2 || height < 2 {
+            return Ok(());
+        }
+
+        let glyphs = self.border.style.glyphs();
+        let attr = self.border.attr;
+        let bottom = height - 1;
+        let right = width - 1;
+
+        for col in 0..width {
+            let _ = canvas.put_char_with_attr(0, col, glyphs.horizontal, attr);
+            let _ = canvas.put_char_with_attr(bottom, col, glyphs.horizontal, attr);
+        }
+
+        if self.border.style.has_sides() {
+            for row in 1..bottom {
+                let _ = canvas.put_char_with_attr(row, 0, glyphs.vertical, attr);
+                let _ = canvas.put_char_with_attr(row, right, glyphs.vertical, attr);
+            }
+
+            let _ = canvas.put_char_with_attr(0, 0, glyphs.top_left, attr);
+            let _ = canvas.put_char_with_attr(0, right, glyphs.top_right, attr);
+            let _ = canvas.put_char_with_attr(bottom, 0, glyphs.bottom_left, attr);
+            let _ = canvas.put_char_with_attr(bottom, right, glyphs.bottom_right, attr);
+        }
+
+        if let Some(label) = self.border.label.as_ref() {
+            let (start, text_width) = label.position(width);
+            let row = if label.bottom { bottom } else { 0 };
+            let mut col = start;
+            for ch in label.text.chars() {
+                let ch_width = ch.width_cjk().unwrap_or(0);
+                if col + ch_width > start + text_width {
+                    break;
+                }
+                let _ = canvas.put_char_with_attr(row, col, ch, self.border.label_attr);
+                col += ch_width;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a, Message> Draw for Bordered<'a, Message> {
+    fn draw(&self, canvas: &mut dyn Canvas) -> DrawResult<()> {
+        self.win.draw(canvas)?;
+        self.draw_border(canvas)
+    }
+}
+
+impl<'a, Message> Widget<Message> for Bordered<'a, Message> {
+    fn size_hint(&self) -> (Option<usize>, Option<usize>) {
+        self.win.size_hint()
+    }
+
+    fn on_event(&self, event: Event, rect: Rectangle) -> Vec<Message> {
+        self.win.on_event(event, rect)
+    }
+
+    fn on_event_mut(&mut self, event: Event, rect: Rectangle) -> Vec<Message> {
+        self.win.on_event_mut(event, rect)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_style() {
+        assert_eq!(BorderStyle::parse(""), Ok(Some(BorderStyle::Rounded)));
+        assert_eq!(BorderStyle::parse("rounded"), Ok(Some(BorderStyle::Rounded)));
+        assert_eq!(BorderStyle::parse("double"), Ok(Some(BorderStyle::Double)));
+        assert_eq!(BorderStyle::parse("horizontal"), Ok(Some(BorderStyle::Horizontal)));
+        assert_eq!(BorderStyle::parse("none"), Ok(None));
+        assert!(BorderStyle::parse("dotted").is_err());
+    }
+
+    #[test]
+    fn test_label_position() {
+        let label = BorderLabel::new("abc", None);
+        assert!(!label.bottom);
+        assert_eq!(label.position(11), (4, 3));
+
+        let label = BorderLabel::new("abc", Some("2"));
+        assert_eq!(label.position(11), (2, 3));
+
+        let label = BorderLabel::new("abc", Some("-2:bottom"));
+        assert!(label.bottom);
+        assert_eq!(label.position(11), (6, 3));
+
+        // clamped to the border, but not over the corners
+        let label = BorderLabel::new("abc", Some("100"));
+        assert_eq!(label.position(11), (7, 3));
+        let label = BorderLabel::new("abcdef", Some("0"));
+        assert_eq!(label.position(5), (1, 3));
+    }
+}
@@ -1,6 +1,7 @@
+use std::cmp::min;
 use std::fmt::{Display, Error, Formatter};
 
-use crate::{MatchEngine, MatchRange, MatchResult, SkimItem};
+use crate::{MatchEngine, MatchRange, MatchResult, SkimItem, TermChars};
 
 //------------------------------------------------------------------------------
 // OrEngine, a combinator
@@ -63,26 +64,29 @@ impl AndEngine {
         self
     }
 
+    // the matched chars are tagged with the index of the term (i.e. engine) that matched them,
+    // a char matched by several terms belongs to the first one
     fn merge_matched_items(&self, items: Vec<MatchResult>, text: &str) -> MatchResult {
         let rank = items[0].rank;
+        let num_terms = items.len();
         let mut ranges = vec![];
-        for item in items {
-            match item.matched_range {
-                MatchRange::ByteRange(..) => {
-                    ranges.extend(item.range_char_indices(text));
-                }
-                MatchRange::Chars(vec) => {
-                    ranges.extend(vec.iter());
-                }
-            }
+        for (term, item) in items.into_iter().enumerate() {
+            let term = min(term, u8::MAX as usize) as u8;
+            ranges.extend(item.range_char_indices(text).into_iter().map(|idx| (idx, term)));
         }
 
-        ranges.sort();
-        ranges.dedup();
-        MatchResult {
-            rank,
-            matched_range: MatchRange::Chars(ranges.into()),
-        }
+        ranges.sort_unstable();
+        ranges.dedup_by_key(|&mut (idx, _)| idx);
+        let (indices, terms): (Vec<usize>, Vec<u8>) = ranges.into_iter().unzip();
+        let matched_range = if num_terms > 1 {
+            MatchRange::TermChars(Box::new(TermChars {
+                indices: indices.into(),
+                terms: terms.into(),
+            }))
+        } else {
+            MatchRange::Chars(indices.into())
+        };
+        MatchResult { rank, matched_range }
     }
 }
 
@@ -116,3 +120,25 @@ impl Display for AndEngine {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::factory::{AndOrEngineFactory, ExactOrFuzzyEngineFactory};
+    use crate::{MatchEngineFactory, MatchRange, TermChars};
+
+    #[test]
+    fn test_matched_terms() {
+        let factory = AndOrEngineFactory::new(Box::new(ExactOrFuzzyEngineFactory::builder().build()));
+
+        let result = factory.create_engine("'cd 'ab").match_item(&"abcd").unwrap();
+        let chars = TermChars {
+            indices: vec![0, 1, 2, 3].into(),
+            terms: vec![1, 1, 0, 0].into(),
+        };
+        assert_eq!(result.matched_range, MatchRange::TermChars(Box::new(chars)));
+
+        // a single term needs no terms
+        let result = factory.create_engine("'ab").match_item(&"abcd").unwrap();
+        assert_eq!(result.matched_range, MatchRange::Chars(vec![0, 1].into()));
+    }
+}
@@ -24,6 +24,7 @@ pub enum Event {
     EvActBackwardWord,
     EvActBeginningOfLine,
     EvActCancel,
+    EvActChangePreviewWindow(String),
     EvActClearScreen,
     EvActDeleteChar,
     EvActDeleteCharEOF,
@@ -34,10 +35,13 @@ pub enum Event {
     EvActExecuteSilent(String),
     EvActForwardChar,
     EvActForwardWord,
+    EvActGrowPreview(i32),
     EvActIfQueryEmpty(String),
     EvActIfQueryNotEmpty(String),
     EvActIfNonMatched(String),
     EvActIgnore,
+    EvActJump,
+    EvActJumpAccept,
     EvActKillLine,
     EvActKillWord,
     EvActNextHistory,
@@ -58,8 +62,13 @@ pub enum Event {
     EvActRotateMode,
     EvActScrollLeft(i32),
     EvActScrollRight(i32),
+    // move the cursor to the item, e.g. clicked on the scrollbar
+    EvActScrollTo(usize),
+    EvActPreviewScrollTo(usize),
     EvActSelectAll,
     EvActSelectRow(usize),
+    EvActShrinkPreview(i32),
+    EvActShowHelp,
     EvActToggle,
     EvActToggleAll,
     EvActToggleIn,
@@ -102,6 +111,7 @@ pub fn parse_event(action: &str, arg: Option<String>) -> Option<Event> {
         "backward-word"        =>   Some(Event::EvActBackwardWord),
         "beginning-of-line"    =>   Some(Event::EvActBeginningOfLine),
         "cancel"               =>   Some(Event::EvActCancel),
+        "change-preview-window" =>  Some(Event::EvActChangePreviewWindow(arg.expect("no arg specified for event change-preview-window"))),
         "clear-screen"         =>   Some(Event::EvActClearScreen),
         "delete-char"          =>   Some(Event::EvActDeleteChar),
         "delete-charEOF"       =>   Some(Event::EvActDeleteCharEOF),
@@ -112,10 +122,13 @@ pub fn parse_event(action: &str, arg: Option<String>) -> Option<Event> {
         "execute-silent"       =>   Some(Event::EvActExecuteSilent(arg.expect("execute-silent event should have argument"))),
         "forward-char"         =>   Some(Event::EvActForwardChar),
         "forward-word"         =>   Some(Event::EvActForwardWord),
+        "grow-preview"         =>   Some(Event::EvActGrowPreview(arg.and_then(|s|s.parse().ok()).unwrap_or(5))),
         "if-non-matched"       =>   Some(Event::EvActIfNonMatched(arg.expect("no arg specified for event if-non-matched"))),
         "if-query-empty"       =>   Some(Event::EvActIfQueryEmpty(arg.expect("no arg specified for event if-query-empty"))),
         "if-query-not-empty"   =>   Some(Event::EvActIfQueryNotEmpty(arg.expect("no arg specified for event if-query-not-empty"))),
         "ignore"               =>   Some(Event::EvActIgnore),
+        "jump"                 =>   Some(Event::EvActJump),
+        "jump-accept"          =>   Some(Event::EvActJumpAccept),
         "kill-line"            =>   Some(Event::EvActKillLine),
         "kill-word"            =>   Some(Event::EvActKillWord),
         "next-history"         =>   Some(Event::EvActNextHistory),
@@ -135,6 +148,8 @@ pub fn parse_event(action: &str, arg: Option<String>) -> Option<Event> {
         "scroll-left"          =>   Some(Event::EvActScrollLeft(arg.and_then(|s|s.parse().ok()).unwrap_or(1))),
         "scroll-right"         =>   Some(Event::EvActScrollRight(arg.and_then(|s|s.parse().ok()).unwrap_or(1))),
         "select-all"           =>   Some(Event::EvActSelectAll),
+        "show-help"            =>   Some(Event::EvActShowHelp),
+        "shrink-preview"       =>   Some(Event::EvActShrinkPreview(arg.and_then(|s|s.parse().ok()).unwrap_or(5))),
         "toggle"               =>   Some(Event::EvActToggle),
         "toggle-all"           =>   Some(Event::EvActToggleAll),
         "toggle-in"            =>   Some(Event::EvActToggleIn),
@@ -150,3 +165,82 @@ pub fn parse_event(action: &str, arg: Option<String>) -> Option<Event> {
         _ => None
     }
 }
+
+/// The action of the event in the form of `--bind`, e.g. `down`, `page-down(2)`, `execute(...)`.
+/// `None` for the events which are not bound to keys.
+#[rustfmt::skip]
+pub fn action_name(event: &Event) -> Option<String> {
+    let with_count = |action: &str, count: i32| if count == 1 {
+        action.to_string()
+    } else {
+        format!("{}({})", action, count)
+    };
+
+    let name = match event {
+        Event::EvActAbort                  => "abort".to_string(),
+        Event::EvActAccept(None)           => "accept".to_string(),
+        Event::EvActAccept(Some(key))      => format!("accept({})", key),
+        Event::EvActAddChar(ch)            => format!("add-char({})", ch),
+        Event::EvActAppendAndSelect        => "append-and-select".to_string(),
+        Event::EvActBackwardChar           => "backward-char".to_string(),
+        Event::EvActBackwardDeleteChar     => "backward-delete-char".to_string(),
+        Event::EvActBackwardKillWord       => "backward-kill-word".to_string(),
+        Event::EvActBackwardWord           => "backward-word".to_string(),
+        Event::EvActBeginningOfLine        => "beginning-of-line".to_string(),
+        Event::EvActCancel                 => "cancel".to_string(),
+        Event::EvActChangePreviewWindow(arg) => format!("change-preview-window({})", arg),
+        Event::EvActClearScreen            => "clear-screen".to_string(),
+        Event::EvActDeleteChar             => "delete-char".to_string(),
+        Event::EvActDeleteCharEOF          => "delete-charEOF".to_string(),
+        Event::EvActDeselectAll            => "deselect-all".to_string(),
+        Event::EvActDown(count)            => with_count("down", *count),
+        Event::EvActEndOfLine              => "end-of-line".to_string(),
+        Event::EvActExecute(cmd)           => format!("execute({})", cmd),
+        Event::EvActExecuteSilent(cmd)     => format!("execute-silent({})", cmd),
+        Event::EvActForwardChar            => "forward-char".to_string(),
+        Event::EvActForwardWord            => "forward-word".to_string(),
+        Event::EvActGrowPreview(step)      => format!("grow-preview({})", step),
+        Event::EvActIfNonMatched(arg)      => format!("if-non-matched({})", arg),
+        Event::EvActIfQueryEmpty(arg)      => format!("if-query-empty({})", arg),
+        Event::EvActIfQueryNotEmpty(arg)   => format!("if-query-not-empty({})", arg),
+        Event::EvActIgnore                 => "ignore".to_string(),
+        Event::EvActJump                   => "jump".to_string(),
+        Event::EvActJumpAccept             => "jump-accept".to_string(),
+        Event::EvActKillLine               => "kill-line".to_string(),
+        Event::EvActKillWord               => "kill-word".to_string(),
+        Event::EvActNextHistory            => "next-history".to_string(),
+        Event::EvActHalfPageDown(count)    => with_count("half-page-down", *count),
+        Event::EvActHalfPageUp(count)      => with_count("half-page-up", *count),
+        Event::EvActPageDown(count)        => with_count("page-down", *count),
+        Event::EvActPageUp(count)          => with_count("page-up", *count),
+        Event::EvActPreviewUp(count)       => with_count("preview-up", *count),
+        Event::EvActPreviewDown(count)     => with_count("preview-down", *count),
+        Event::EvActPreviewLeft(count)     => with_count("preview-left", *count),
+        Event::EvActPreviewRight(count)    => with_count("preview-right", *count),
+        Event::EvActPreviewPageUp(count)   => with_count("preview-page-up", *count),
+        Event::EvActPreviewPageDown(count) => with_count("preview-page-down", *count),
+        Event::EvActPreviousHistory        => "previous-history".to_string(),
+        Event::EvActRefreshCmd             => "refresh-cmd".to_string(),
+        Event::EvActRefreshPreview         => "refresh-preview".to_string(),
+        Event::EvActRotateMode             => "rotate-mode".to_string(),
+        Event::EvActScrollLeft(count)      => with_count("scroll-left", *count),
+        Event::EvActScrollRight(count)     => with_count("scroll-right", *count),
+        Event::EvActSelectAll              => "select-all".to_string(),
+        Event::EvActShowHelp               => "show-help".to_string(),
+        Event::EvActShrinkPreview(step)    => format!("shrink-preview({})", step),
+        Event::EvActToggle                 => "toggle".to_string(),
+        Event::EvActToggleAll              => "toggle-all".to_string(),
+        Event::EvActToggleIn               => "toggle-in".to_string(),
+        Event::EvActToggleInteractive      => "toggle-interactive".to_string(),
+        Event::EvActToggleOut              => "toggle-out".to_string(),
+        Event::EvActTogglePreview          => "toggle-preview".to_string(),
+        Event::EvActTogglePreviewWrap      => "toggle-preview-wrap".to_string(),
+        Event::EvActToggleSort             => "toggle-sort".to_string(),
+        Event::EvActUnixLineDiscard        => "unix-line-discard".to_string(),
+        Event::EvActUnixWordRubout         => "unix-word-rubout".to_string(),
+        Event::EvActUp(count)              => with_count("up", *count),
+        Event::EvActYank                   => "yank".to_string(),
+        _ => return None,
+    };
+    Some(name)
+}
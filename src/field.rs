@@ -99,7 +99,7 @@ impl FieldRange {
 
 // ("|", "a|b||c") -> [(0, 2), (2, 4), (4, 5), (5, 6)]
 // explain: split to ["a|", "b|", "|", "c"]
-fn get_ranges_by_delimiter(delimiter: &Regex, text: &str) -> Vec<(usize, usize)> {
+pub(crate) fn get_ranges_by_delimiter(delimiter: &Regex, text: &str) -> Vec<(usize, usize)> {
     let mut ranges = Vec::new();
     let mut last = 0;
     for mat in delimiter.find_iter(text) {
@@ -0,0 +1,195 @@
+//! Grouped results, the items are listed under the headers of their groups (`SkimItem::group`)
+//!
+//! The items are ranked within their groups, the groups are ordered by their best items, or by
+//! the first items read. Items without a group are listed first, without a header.
+use std::collections::HashMap;
+
+use crate::item::MatchedItem;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupOrder {
+    /// the group of the best match comes first
+    Rank,
+    /// the group of the first item read comes first
+    Index,
+}
+
+impl GroupOrder {
+    /// `rank` or `index`
+    pub fn parse(order: &str) -> Result<Self, String> {
+        match order {
+            "rank" => Ok(GroupOrder::Rank),
+            "index" => Ok(GroupOrder::Index),
+            _ => Err(format!("unknown group order '{}'", order)),
+        }
+    }
+}
+
+pub enum GroupRow {
+    Header(String),
+    Item(MatchedItem),
+}
+
+pub struct Groups {
+    order: GroupOrder,
+    // the header row follows the items of its group
+    headers_after: bool,
+    rows: Vec<GroupRow>,
+}
+
+impl Groups {
+    pub fn new(order: GroupOrder) -> Self {
+        Self {
+            order,
+            headers_after: false,
+            rows: Vec::new(),
+        }
+    }
+
+    /// put the headers after the items of their groups, so that they are still drawn above them
+    /// in the bottom-up layout
+    pub fn headers_after(mut self, headers_after: bool) -> Self {
+        self.headers_after = headers_after;
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&GroupRow> {
+        self.rows.get(index)
+    }
+
+    pub fn is_header(&self, index: usize) -> bool {
+        matches!(self.rows.get(index), Some(GroupRow::Header(_)))
+    }
+
+    /// rebuild the rows from the ranked items
+    pub fn build(&mut self, items: impl Iterator<Item = MatchedItem>) {
+        let mut ungrouped = Vec::new();
+        // (group, the index of the first item read, items)
+        let mut groups: Vec<(String, u32, Vec<MatchedItem>)> = Vec::new();
+        let mut index: HashMap<String, usize> = HashMap::new();
+
+        for item in items {
+            let group = match item.upgrade_infallible().group() {
+                Some(group) => group.to_string(),
+                None => {
+                    ungrouped.push(item);
+                    continue;
+                }
+            };
+
+            let group_idx = *index.entry(group.clone()).or_insert_with(|| {
+                groups.push((group, item.item_idx, Vec::new()));
+                groups.len() - 1
+            });
+            let (_, first_idx, group_items) = &mut groups[group_idx];
+            *first_idx = (*first_idx).min(item.item_idx);
+            group_items.push(item);
+        }
+
+        if self.order == GroupOrder::Index {
+            groups.sort_by_key(|&(_, first_idx, _)| first_idx);
+        }
+
+        let mut rows = Vec::with_capacity(ungrouped.len() + groups.len() * 2);
+        rows.extend(ungrouped.into_iter().map(GroupRow::Item));
+        for (group, _, group_items) in groups {
+            if self.headers_after {
+                rows.extend(group_items.into_iter().map(GroupRow::Item));
+                rows.push(GroupRow::Header(group));
+            } else {
+                rows.push(GroupRow::Header(group));
+                rows.extend(group_items.into_iter().map(GroupRow::Item));
+            }
+        }
+
+        self.rows = rows;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SkimItem;
+    use std::borrow::Cow;
+    use std::sync::Arc;
+
+    struct GroupedItem(&'static str, &'static str);
+
+    impl SkimItem for GroupedItem {
+        fn text(&self) -> Cow<str> {
+            Cow::Borrowed(self.0)
+        }
+
+        fn group(&self) -> Option<Cow<str>> {
+            if self.1.is_empty() {
+                None
+            } else {
+                Some(Cow::Borrowed(self.1))
+            }
+        }
+    }
+
+    fn rows(groups: &Groups) -> Vec<String> {
+        groups
+            .rows
+            .iter()
+            .map(|row| match row {
+                GroupRow::Header(group) => format!("[{}]", group),
+                GroupRow::Item(item) => item.upgrade_infallible().text().to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_build() {
+        let items: Vec<Arc<dyn SkimItem>> = vec![
+            Arc::new(GroupedItem("a.rs", "files")),
+            Arc::new(GroupedItem("main", "buffers")),
+            Arc::new(GroupedItem("none", "")),
+            Arc::new(GroupedItem("b.rs", "files")),
+        ];
+        // ranked: "main" is the best match, then the items in their order
+        let ranked = || {
+            [1, 0, 2, 3].iter().map(|&idx| MatchedItem {
+                item: Arc::downgrade(&items[idx]),
+                rank: Default::default(),
+                matched_range: None,
+                item_idx: idx as u32,
+            })
+        };
+
+        let mut groups = Groups::new(GroupOrder::Rank);
+        groups.build(ranked());
+        assert_eq!(
+            rows(&groups),
+            vec!["none", "[buffers]", "main", "[files]", "a.rs", "b.rs"]
+        );
+        assert!(groups.is_header(1));
+        assert!(!groups.is_header(2));
+
+        let mut groups = Groups::new(GroupOrder::Index);
+        groups.build(ranked());
+        assert_eq!(
+            rows(&groups),
+            vec!["none", "[files]", "a.rs", "b.rs", "[buffers]", "main"]
+        );
+
+        let mut groups = Groups::new(GroupOrder::Rank).headers_after(true);
+        groups.build(ranked());
+        assert_eq!(
+            rows(&groups),
+            vec!["none", "main", "[buffers]", "a.rs", "b.rs", "[files]"]
+        );
+    }
+
+    #[test]
+    fn test_parse_order() {
+        assert_eq!(GroupOrder::parse("rank"), Ok(GroupOrder::Rank));
+        assert_eq!(GroupOrder::parse("index"), Ok(GroupOrder::Index));
+        assert!(GroupOrder::parse("name").is_err());
+    }
+}
@@ -68,6 +68,10 @@ impl Header {
         self
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.lines_of_header() == 0
+    }
+
     fn lines_of_header(&self) -> usize {
         Weak::upgrade(&self.item_pool)
             .map(|upgraded| self.header.len() + upgraded.reserved().len())
@@ -137,6 +141,8 @@ impl Draw for Header {
                     matches: None,
                     container_width: screen_width - 2,
                     highlight_attr: self.theme.header(),
+                    matched_terms: None,
+                    highlight_palette: &[],
                 };
 
                 print_item(canvas, &mut printer, item.display(context), self.theme.header());
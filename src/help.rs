@@ -0,0 +1,231 @@
+//! The help overlay (`show-help`), the key bindings with their action chains
+//!
+//! The bindings are filtered by their own query with the fuzzy engine of the items, the keys
+//! typed while the overlay is shown edit the query or scroll the list, `esc` closes it.
+use std::cmp::{max, min};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use tuikit::prelude::*;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::engine::factory::{AndOrEngineFactory, ExactOrFuzzyEngineFactory};
+use crate::event::Event;
+use crate::theme::{ColorTheme, DEFAULT_THEME};
+use crate::util::clear_canvas;
+use crate::{CaseMatching, MatchEngineFactory, SkimItem, SkimOptions};
+
+const PROMPT: &str = "> ";
+
+pub struct Help {
+    // "key  action+action", the keys padded to the same width
+    bindings: Vec<Arc<dyn SkimItem>>,
+    key_width: usize,
+    engine_factory: Box<dyn MatchEngineFactory>,
+    case: CaseMatching,
+    theme: Arc<ColorTheme>,
+
+    visible: bool,
+    query: String,
+    // the matched bindings, with the indices of the matched chars
+    matched: Vec<(usize, Vec<usize>)>,
+    // the first binding shown
+    offset: usize,
+    // the number of bindings shown at the last draw
+    height: AtomicUsize,
+}
+
+impl Help {
+    pub fn with_options(options: &SkimOptions, bindings: Vec<(String, String)>) -> Self {
+        let key_width = bindings.iter().map(|(key, _)| key.width()).max().unwrap_or(0);
+        let bindings: Vec<Arc<dyn SkimItem>> = bindings
+            .into_iter()
+            .map(|(key, actions)| {
+                let padding = " ".repeat(key_width - key.width());
+                Arc::new(format!("{}{}  {}", key, padding, actions)) as Arc<dyn SkimItem>
+            })
+            .collect();
+
+        let engine_factory = AndOrEngineFactory::new(Box::new(
+            ExactOrFuzzyEngineFactory::builder()
+                .fuzzy_algorithm(options.algorithm)
+                .exact_mode(options.exact)
+                .build(),
+        ));
+
+        let mut help = Self {
+            bindings,
+            key_width,
+            engine_factory: Box::new(engine_factory),
+            case: options.case,
+            theme: Arc::new(*DEFAULT_THEME),
+            visible: false,
+            query: String::new(),
+            matched: Vec::new(),
+            offset: 0,
+            height: AtomicUsize::new(0),
+        };
+        help.filter();
+        help
+    }
+
+    pub fn theme(mut self, theme: Arc<ColorTheme>) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn show(&mut self) {
+        self.visible = true;
+        self.query.clear();
+        self.filter();
+    }
+
+    /// handle the events of a key while the help is shown
+    pub fn handle(&mut self, key: Key, event: &Event) {
+        let height = max(self.height.load(Ordering::Relaxed), 1) as i32;
+        match event {
+            _ if key == Key::ESC => self.visible = false,
+            Event::EvActAbort | Event::EvActCancel | Event::EvActShowHelp => self.visible = false,
+            Event::EvActAddChar(ch) => {
+                self.query.push(*ch);
+                self.filter();
+            }
+            Event::EvActBackwardDeleteChar => {
+                self.query.pop();
+                self.filter();
+            }
+            Event::EvActUnixLineDiscard => {
+                self.query.clear();
+                self.filter();
+            }
+            Event::EvActUp(diff) => self.scroll(-diff),
+            Event::EvActDown(diff) => self.scroll(*diff),
+            Event::EvActPageUp(diff) => self.scroll(-diff * height),
+            Event::EvActPageDown(diff) => self.scroll(diff * height),
+            Event::EvActHalfPageUp(diff) => self.scroll(-diff * height / 2),
+            Event::EvActHalfPageDown(diff) => self.scroll(diff * height / 2),
+            _ => {}
+        }
+    }
+
+    fn scroll(&mut self, diff: i32) {
+        let height = self.height.load(Ordering::Relaxed);
+        let max_offset = self.matched.len().saturating_sub(height);
+        let offset = max(self.offset as i32 + diff, 0) as usize;
+        self.offset = min(offset, max_offset);
+    }
+
+    /// match the bindings against the query, the best matches first
+    fn filter(&mut self) {
+        self.offset = 0;
+        if self.query.is_empty() {
+            self.matched = (0..self.bindings.len()).map(|idx| (idx, Vec::new())).collect();
+            return;
+        }
+
+        let engine = self.engine_factory.create_engine_with_case(&self.query, self.case);
+        let mut matched: Vec<_> = self
+            .bindings
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, binding)| {
+                let result = engine.match_item(binding.as_ref())?;
+                let indices = result.range_char_indices(&binding.text());
+                Some((result.rank, idx, indices))
+            })
+            .collect();
+        matched.sort_by_key(|&(rank, idx, _)| (rank, idx));
+        self.matched = matched.into_iter().map(|(_, idx, indices)| (idx, indices)).collect();
+    }
+}
+
+impl Draw for Help {
+    fn draw(&self, canvas: &mut dyn Canvas) -> DrawResult<()> {
+        let (screen_width, screen_height) = canvas.size()?;
+        canvas.clear()?;
+        clear_canvas(canvas)?;
+
+        let prompt_width = canvas.print_with_attr(0, 0, PROMPT, self.theme.prompt())?;
+        let query_width = canvas.print_with_attr(0, prompt_width, &self.query, self.theme.query())?;
+        let info = format!("{}/{}", self.matched.len(), self.bindings.len());
+        let info_col = prompt_width + query_width + 2;
+        if info_col + info.width() <= screen_width {
+            canvas.print_with_attr(0, info_col, &info, self.theme.info())?;
+        }
+        canvas.set_cursor(0, prompt_width + query_width)?;
+        canvas.show_cursor(true)?;
+
+        let height = screen_height.saturating_sub(1);
+        self.height.store(height, Ordering::Relaxed);
+
+        let rows = self.matched.iter().skip(self.offset).take(height);
+        for (row, (idx, indices)) in (1..).zip(rows) {
+            let text = self.bindings[*idx].text();
+            let mut col = 0;
+            let mut indices = indices.iter().peekable();
+            for (ch_idx, ch) in text.chars().enumerate() {
+                let ch_width = ch.width().unwrap_or(0);
+                if col + ch_width > screen_width {
+                    break;
+                }
+
+                let mut attr = if col < self.key_width {
+                    self.theme.header()
+                } else {
+                    self.theme.normal()
+                };
+                if indices.peek() == Some(&&ch_idx) {
+                    indices.next();
+                    attr = attr.extend(self.theme.matched());
+                }
+                canvas.put_char_with_attr(row, col, ch, attr)?;
+                col += ch_width;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Widget<Event> for Help {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(help: &Help) -> Vec<String> {
+        help.matched
+            .iter()
+            .map(|(idx, _)| help.bindings[*idx].text().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_filter() {
+        let bindings = vec![
+            ("ctrl-a".to_string(), "beginning-of-line".to_string()),
+            ("f1".to_string(), "show-help".to_string()),
+            ("tab".to_string(), "toggle+down".to_string()),
+        ];
+        let mut help = Help::with_options(&SkimOptions::default(), bindings);
+        help.show();
+        assert!(help.is_visible());
+        assert_eq!(texts(&help).len(), 3);
+
+        for ch in "help".chars() {
+            help.handle(Key::Char(ch), &Event::EvActAddChar(ch));
+        }
+        assert_eq!(texts(&help), vec!["f1      show-help"]);
+        assert_eq!(help.matched[0].1, vec![13, 14, 15, 16]);
+
+        help.handle(Key::Ctrl('u'), &Event::EvActUnixLineDiscard);
+        assert_eq!(texts(&help).len(), 3);
+
+        help.handle(Key::ESC, &Event::EvActAbort);
+        assert!(!help.is_visible());
+    }
+}
@@ -0,0 +1,91 @@
+//! Arena storage for raw items
+//!
+//! Storing every raw line as its own `Box<str>` costs one allocation (and its bookkeeping) per
+//! line on top of the `Arc<dyn SkimItem>`. Instead, the lines read in one batch are copied into a
+//! single contiguous chunk, and each item only keeps a reference to the chunk plus offsets.
+//! A chunk is freed once all the items referring to it are dropped.
+use std::convert::TryFrom;
+use std::sync::Arc;
+
+// `Box<str>` inside, so that the `Arc` is a thin pointer and keeps `ArenaItem` small
+type Chunk = Arc<Box<str>>;
+
+/// A raw item whose text lives in a shared arena chunk.
+/// `SkimItem` is implemented through `AsRef<str>`.
+#[derive(Debug, Clone)]
+pub struct ArenaItem {
+    chunk: Chunk,
+    start: u32,
+    end: u32,
+}
+
+impl AsRef<str> for ArenaItem {
+    fn as_ref(&self) -> &str {
+        &self.chunk[self.start as usize..self.end as usize]
+    }
+}
+
+impl ArenaItem {
+    /// `range` should be the one returned by `ArenaBuilder::push` for the `chunk`
+    pub fn new(chunk: &Chunk, range: (u32, u32)) -> Self {
+        Self {
+            chunk: chunk.clone(),
+            start: range.0,
+            end: range.1,
+        }
+    }
+}
+
+/// Collects the text of a batch of items, then freezes it into a chunk shared by the items.
+#[derive(Debug, Default)]
+pub struct ArenaBuilder {
+    buffer: String,
+}
+
+impl ArenaBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// copy `text` into the current chunk, return its range in the chunk.
+    /// `None` if the chunk could not be addressed by `u32` offsets anymore.
+    pub fn push(&mut self, text: &str) -> Option<(u32, u32)> {
+        let start = u32::try_from(self.buffer.len()).ok()?;
+        let end = u32::try_from(self.buffer.len() + text.len()).ok()?;
+        self.buffer.push_str(text);
+        Some((start, end))
+    }
+
+    /// finish the current chunk, the builder could be reused for the next one
+    pub fn freeze(&mut self) -> Chunk {
+        let chunk: Chunk = Arc::new(self.buffer.as_str().into());
+        self.buffer.clear();
+        chunk
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SkimItem;
+
+    #[test]
+    fn test_items_share_chunk() {
+        let mut builder = ArenaBuilder::new();
+        let ranges: Vec<_> = ["foo", "", "bar baz"]
+            .iter()
+            .map(|line| builder.push(line).unwrap())
+            .collect();
+        let chunk = builder.freeze();
+        assert_eq!(builder.push("next"), Some((0, 4)));
+
+        let items: Vec<ArenaItem> = ranges.into_iter().map(|range| ArenaItem::new(&chunk, range)).collect();
+        assert_eq!(items[0].text(), "foo");
+        assert_eq!(items[1].text(), "");
+        assert_eq!(items[2].text(), "bar baz");
+        assert_eq!(Arc::strong_count(&chunk), 4);
+
+        drop(items);
+        assert_eq!(Arc::strong_count(&chunk), 1);
+    }
+}
@@ -18,7 +18,18 @@ use std::sync::Weak;
 #[cfg(target_env = "gnu")]
 use crate::malloc_trim;
 
+use super::arena::{ArenaBuilder, ArenaItem};
 use super::item::DefaultSkimItem;
+use super::mmap::{line_ranges, split_segments, MmapItem};
+use memmap2::Mmap;
+use rayon::prelude::*;
+
+// lines of a mapped file are split in parallel by segments of this size
+const MMAP_SEGMENT_SIZE: usize = 1 << 20;
+// number of segments split before the items are sent, so that the first ones show up early
+const MMAP_SEGMENTS_PER_BATCH: usize = 64;
+
+type StringIntern = HashMap<u64, Weak<dyn SkimItem>, BuildHasherDefault<NoHashHasher<u64>>>;
 
 #[derive(Clone)]
 pub enum SendRawOrBuild<'a> {
@@ -43,8 +54,8 @@ pub fn ingest_loop(
 ) {
     let mut bytes_buffer = Vec::with_capacity(65_536);
 
-    let mut string_intern: HashMap<u64, Weak<dyn SkimItem>, BuildHasherDefault<NoHashHasher<u64>>> =
-        HashMap::with_capacity_and_hasher(8192, BuildHasherDefault::default());
+    let mut string_intern: StringIntern = HashMap::with_capacity_and_hasher(8192, BuildHasherDefault::default());
+    let mut arena = ArenaBuilder::new();
 
     loop {
         // first, read lots of bytes into the buffer
@@ -71,11 +82,17 @@ pub fn ingest_loop(
             break;
         }
 
-        if let Err(_err) = std::str::from_utf8_mut(&mut bytes_buffer)
-            .expect("Could not convert bytes to valid UTF8.")
-            .lines()
-            .try_for_each(|line| send(line, &opts, &tx_item, &mut string_intern))
-        {
+        let text = std::str::from_utf8(&bytes_buffer).expect("Could not convert bytes to valid UTF8.");
+        let res = match &opts {
+            SendRawOrBuild::Raw => {
+                send_raw_batch(split_lines(text, line_ending), &tx_item, &mut string_intern, &mut arena)
+            }
+            SendRawOrBuild::Build(opts) => {
+                split_lines(text, line_ending).try_for_each(|line| send(line, opts, &tx_item, &mut string_intern))
+            }
+        };
+
+        if let Err(_err) = res {
             break;
         }
 
@@ -92,33 +109,37 @@ pub fn ingest_loop(
     })
 }
 
+// split the text by `line_ending`, a trailing "\r" is also stripped for "\n" (as `str::lines`).
+// With other line endings (i.e. `--read0`), the lines are kept as they are, newlines included.
+fn split_lines(text: &str, line_ending: u8) -> impl Iterator<Item = &str> {
+    let line_ending = line_ending as char;
+    text.split_terminator(line_ending).map(move |line| {
+        if line_ending == '\n' {
+            line.strip_suffix('\r').unwrap_or(line)
+        } else {
+            line
+        }
+    })
+}
+
 fn send(
     line: &str,
-    opts: &SendRawOrBuild,
+    opts: &BuildOptions,
     tx_item: &Sender<Arc<dyn SkimItem>>,
-    string_intern: &mut HashMap<u64, Weak<dyn SkimItem>, BuildHasherDefault<NoHashHasher<u64>>>,
+    string_intern: &mut StringIntern,
 ) -> Result<(), SendError<Arc<dyn SkimItem>>> {
     let key = hash(&line.as_bytes());
 
     match string_intern.get(&key).and_then(|value| Weak::upgrade(value)) {
         Some(value) => tx_item.send(value),
         None => {
-            let item: Arc<dyn SkimItem> = match opts {
-                SendRawOrBuild::Build(opts) => {
-                    let item = DefaultSkimItem::new(
-                        line,
-                        opts.ansi_enabled,
-                        opts.trans_fields,
-                        opts.matching_fields,
-                        opts.delimiter,
-                    );
-                    Arc::new(item)
-                }
-                SendRawOrBuild::Raw => {
-                    let item: Box<str> = line.into();
-                    Arc::new(item)
-                }
-            };
+            let item: Arc<dyn SkimItem> = Arc::new(DefaultSkimItem::new(
+                line,
+                opts.ansi_enabled,
+                opts.trans_fields,
+                opts.matching_fields,
+                opts.delimiter,
+            ));
 
             string_intern.insert_unique_unchecked(key, Arc::downgrade(&item));
             tx_item.send(item)
@@ -126,6 +147,106 @@ fn send(
     }
 }
 
+enum RawLine {
+    Ready(Arc<dyn SkimItem>),
+    // hash of the line and its range in the arena chunk
+    InArena(u64, (u32, u32)),
+}
+
+// Raw lines are stored in an arena chunk per batch. The new lines are copied into the chunk first,
+// items could only be created after the chunk is frozen.
+fn send_raw_batch<'a>(
+    lines: impl Iterator<Item = &'a str>,
+    tx_item: &Sender<Arc<dyn SkimItem>>,
+    string_intern: &mut StringIntern,
+    arena: &mut ArenaBuilder,
+) -> Result<(), SendError<Arc<dyn SkimItem>>> {
+    let lines: Vec<RawLine> = lines
+        .map(|line| {
+            let key = hash(line.as_bytes());
+
+            if let Some(item) = string_intern.get(&key).and_then(Weak::upgrade) {
+                return RawLine::Ready(item);
+            }
+
+            match arena.push(line) {
+                Some(range) => RawLine::InArena(key, range),
+                None => {
+                    let item: Box<str> = line.into();
+                    let item: Arc<dyn SkimItem> = Arc::new(item);
+                    string_intern.insert(key, Arc::downgrade(&item));
+                    RawLine::Ready(item)
+                }
+            }
+        })
+        .collect();
+
+    let chunk = arena.freeze();
+
+    lines.into_iter().try_for_each(|line| {
+        let item = match line {
+            RawLine::Ready(item) => item,
+            RawLine::InArena(key, range) => match string_intern.get(&key).and_then(Weak::upgrade) {
+                // duplicated line in the same batch
+                Some(item) => item,
+                None => {
+                    let item: Arc<dyn SkimItem> = Arc::new(ArenaItem::new(&chunk, range));
+                    string_intern.insert(key, Arc::downgrade(&item));
+                    item
+                }
+            },
+        };
+
+        tx_item.send(item)
+    })
+}
+
+/// create items from a memory-mapped file, raw items borrow the text from the mapping
+pub fn ingest_mmap(map: Mmap, line_ending: u8, tx_item: Sender<Arc<dyn SkimItem>>, opts: SendRawOrBuild) {
+    let map = Arc::new(map);
+    let segments = split_segments(&map, line_ending, MMAP_SEGMENT_SIZE);
+
+    for batch in segments.chunks(MMAP_SEGMENTS_PER_BATCH) {
+        // collecting keeps the order of the segments
+        let items: Vec<Vec<Arc<dyn SkimItem>>> = batch
+            .par_iter()
+            .map(|&(seg_start, seg_end)| {
+                line_ranges(&map[seg_start..seg_end], line_ending)
+                    .map(|(start, end)| build_mmap_item(&map, seg_start + start, seg_start + end, &opts))
+                    .collect()
+            })
+            .collect();
+
+        if items
+            .into_iter()
+            .flatten()
+            .try_for_each(|item| tx_item.send(item))
+            .is_err()
+        {
+            break;
+        }
+    }
+}
+
+fn build_mmap_item(map: &Arc<Mmap>, start: usize, end: usize, opts: &SendRawOrBuild) -> Arc<dyn SkimItem> {
+    match opts {
+        SendRawOrBuild::Raw => match MmapItem::new(map, start, end) {
+            Some(item) => Arc::new(item),
+            None => {
+                let item: Box<str> = String::from_utf8_lossy(&map[start..end]).into();
+                Arc::new(item)
+            }
+        },
+        SendRawOrBuild::Build(opts) => Arc::new(DefaultSkimItem::new(
+            &String::from_utf8_lossy(&map[start..end]),
+            opts.ansi_enabled,
+            opts.trans_fields,
+            opts.matching_fields,
+            opts.delimiter,
+        )),
+    }
+}
+
 #[inline]
 fn hash(bytes: &[u8]) -> u64 {
     use std::hash::Hasher;
@@ -135,3 +256,17 @@ fn hash(bytes: &[u8]) -> u64 {
     hash.write(bytes);
     hash.finish()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_lines() {
+        let lines: Vec<&str> = split_lines("a\r\nb\n\nc\n", b'\n').collect();
+        assert_eq!(lines, vec!["a", "b", "", "c"]);
+
+        let lines: Vec<&str> = split_lines("a\nb\0c\r\n\0", b'\0').collect();
+        assert_eq!(lines, vec!["a\nb", "c\r\n"]);
+    }
+}
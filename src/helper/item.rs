@@ -109,7 +109,8 @@ impl SkimItem for DefaultSkimItem {
         let new_fragments: Vec<(Attr, (u32, u32))> = match context.matches {
             Some(Matches::CharIndices(indices)) => indices
                 .iter()
-                .map(|&idx| (context.highlight_attr, (idx as u32, idx as u32 + 1)))
+                .enumerate()
+                .map(|(nth, &idx)| (context.char_highlight(nth), (idx as u32, idx as u32 + 1)))
                 .collect(),
             Some(Matches::CharRange(start, end)) => vec![(context.highlight_attr, (start as u32, end as u32))],
             Some(Matches::ByteRange(start, end)) => {
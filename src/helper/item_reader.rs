@@ -1,10 +1,10 @@
 /// helper for turn a BufRead into a skim stream
 use std::env;
 use std::error::Error;
+use std::fs::File;
 use std::io::{BufRead, BufReader};
 
 use std::process::{Child, Command, Stdio};
-use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::thread::{self, JoinHandle};
 
@@ -12,11 +12,14 @@ use crossbeam_channel::{bounded, unbounded, Receiver, Sender};
 use regex::Regex;
 
 use crate::field::FieldRange;
-use crate::helper::ingest::{ingest_loop, BuildOptions, SendRawOrBuild};
+use crate::helper::ingest::{ingest_loop, ingest_mmap, BuildOptions, SendRawOrBuild};
+use crate::helper::mmap::map_file;
 use crate::reader::CommandCollector;
+use crate::wait_group::WaitGroup;
 use crate::{SkimItem, SkimItemReceiver, SkimItemSender};
 
 const CMD_CHANNEL_SIZE: usize = 1_024;
+const READ_BUFFER_CAPACITY: usize = 65_536;
 const DELIMITER_STR: &str = r"[\t\n ]+";
 
 pub enum CollectorInput {
@@ -142,12 +145,41 @@ impl SkimItemReader {
         if self.option.is_simple() {
             self.raw_bufread(source)
         } else {
-            let (rx_item, _tx_item, opt_ingest_handle) = self
-                .read_and_collect_from_command(Arc::new(AtomicUsize::new(0)), CollectorInput::Pipe(Box::new(source)));
+            let (rx_item, _tx_item, opt_ingest_handle) =
+                self.read_and_collect_from_command(Arc::new(WaitGroup::new()), CollectorInput::Pipe(Box::new(source)));
             (rx_item, opt_ingest_handle)
         }
     }
 
+    /// read items from `file`, which is memory-mapped if it is a regular file,
+    /// otherwise it is read as a bufread.
+    pub fn of_file(&self, file: File) -> (SkimItemReceiver, Option<JoinHandle<()>>) {
+        let map = match map_file(&file) {
+            Some(map) => map,
+            None => return self.of_bufread(Box::new(BufReader::with_capacity(READ_BUFFER_CAPACITY, file))),
+        };
+
+        let (tx_item, rx_item): (SkimItemSender, SkimItemReceiver) = unbounded();
+        let option = self.option.clone();
+
+        let ingest_handle = thread::spawn(move || {
+            if option.is_simple() {
+                ingest_mmap(map, option.line_ending, tx_item, SendRawOrBuild::Raw);
+            } else {
+                let opts = BuildOptions {
+                    ansi_enabled: option.use_ansi_color,
+                    trans_fields: &option.transform_fields,
+                    matching_fields: &option.matching_fields,
+                    delimiter: &option.delimiter,
+                };
+
+                ingest_mmap(map, option.line_ending, tx_item, SendRawOrBuild::Build(opts));
+            }
+        });
+
+        (rx_item, Some(ingest_handle))
+    }
+
     /// helper: convert bufread into SkimItemReceiver
     fn raw_bufread(&self, source: Box<dyn BufRead + Send>) -> (SkimItemReceiver, Option<JoinHandle<()>>) {
         let (tx_item, rx_item): (SkimItemSender, SkimItemReceiver) = unbounded();
@@ -160,12 +192,12 @@ impl SkimItemReader {
         (rx_item, Some(ingest_handle))
     }
 
-    /// components_to_stop == 0 => all the threads have been stopped
+    /// components_to_stop.all_stopped() => all the threads have been stopped
     /// return (channel_for_receive_item, channel_to_stop_command)
     #[allow(clippy::type_complexity)]
     fn read_and_collect_from_command(
         &self,
-        components_to_stop: Arc<AtomicUsize>,
+        components_to_stop: Arc<WaitGroup>,
         input: CollectorInput,
     ) -> (Receiver<Arc<dyn SkimItem>>, Sender<i32>, Option<JoinHandle<()>>) {
         let (tx_interrupt, rx_interrupt) = bounded(CMD_CHANNEL_SIZE);
@@ -173,15 +205,12 @@ impl SkimItemReader {
 
         match input {
             CollectorInput::Pipe(source) => {
-                let started = Arc::new(AtomicBool::new(false));
-                let started_clone = started.clone();
                 let tx_interrupt_clone = tx_interrupt.clone();
                 let option = self.option.clone();
+                // register before spawning, so that the collector is never seen as stopped
+                components_to_stop.add(1);
                 let ingest_handle = thread::spawn(move || {
                     debug!("collector: command collector start");
-                    components_to_stop.fetch_add(1, Ordering::SeqCst);
-                    started_clone.store(true, Ordering::SeqCst);
-                    // notify parent that it is started
 
                     let opts = BuildOptions {
                         ansi_enabled: option.use_ansi_color,
@@ -193,28 +222,22 @@ impl SkimItemReader {
                     ingest_loop(source, option.line_ending, tx_item, SendRawOrBuild::Build(opts));
 
                     let _ = tx_interrupt_clone.send(1); // ensure the waiting thread will exit
-                    components_to_stop.fetch_sub(1, Ordering::SeqCst);
+                    components_to_stop.done();
                     debug!("collector: command collector stop");
                 });
 
-                while !started.load(Ordering::SeqCst) {
-                    // busy waiting for the thread to start. (components_to_stop is added)
-                }
-
                 (rx_item, tx_interrupt, Some(ingest_handle))
             }
             CollectorInput::Command(cmd) => {
                 let command = get_command_output(&cmd).expect("command not found").0;
 
-                let started = Arc::new(AtomicBool::new(false));
-                let started_clone = started.clone();
                 let components_to_stop_clone = components_to_stop;
                 let send_error = self.option.show_error;
+                // register before spawning, so that the collector is never seen as stopped
+                components_to_stop_clone.add(1);
                 // listening to close signal and kill command if needed
                 let ingest_handle = thread::spawn(move || {
                     debug!("collector: command killer start");
-                    components_to_stop_clone.fetch_add(1, Ordering::SeqCst);
-                    started_clone.store(true, Ordering::SeqCst); // notify parent that it is started
 
                     let _ = rx_interrupt.recv(); // block waiting
                     if let Some(mut child) = command {
@@ -236,14 +259,10 @@ impl SkimItemReader {
                         }
                     }
 
-                    components_to_stop_clone.fetch_sub(1, Ordering::SeqCst);
+                    components_to_stop_clone.done();
                     debug!("collector: command killer stop");
                 });
 
-                while !started.load(Ordering::SeqCst) {
-                    // busy waiting for the thread to start. (components_to_stop is added)
-                }
-
                 (rx_item, tx_interrupt, Some(ingest_handle))
             }
         }
@@ -254,7 +273,7 @@ impl CommandCollector for SkimItemReader {
     fn invoke(
         &mut self,
         cmd: &str,
-        components_to_stop: Arc<AtomicUsize>,
+        components_to_stop: Arc<WaitGroup>,
     ) -> (SkimItemReceiver, Sender<i32>, Option<JoinHandle<()>>) {
         self.read_and_collect_from_command(components_to_stop, CollectorInput::Command(cmd.to_string()))
     }
@@ -0,0 +1,146 @@
+//! Memory-mapped input
+//!
+//! When the input is a regular file, it is mapped into memory instead of being read and copied
+//! through a buffer. Raw items borrow their text from the mapping, which is unmapped once all the
+//! items referring to it are dropped.
+use std::fs::File;
+use std::io::Seek;
+use std::sync::Arc;
+
+use memmap2::{Mmap, MmapOptions};
+
+/// A raw item whose text lives in a memory-mapped file.
+/// `SkimItem` is implemented through `AsRef<str>`.
+#[derive(Debug, Clone)]
+pub struct MmapItem {
+    map: Arc<Mmap>,
+    start: usize,
+    end: usize,
+}
+
+impl MmapItem {
+    /// `None` if the bytes in the range are not valid UTF-8
+    pub fn new(map: &Arc<Mmap>, start: usize, end: usize) -> Option<Self> {
+        std::str::from_utf8(&map[start..end]).ok()?;
+        Some(Self {
+            map: map.clone(),
+            start,
+            end,
+        })
+    }
+}
+
+impl AsRef<str> for MmapItem {
+    fn as_ref(&self) -> &str {
+        // Safety: the range is checked to be valid UTF-8 on creation, and the mapping is read-only
+        unsafe { std::str::from_utf8_unchecked(&self.map[self.start..self.end]) }
+    }
+}
+
+/// map `file` into memory from its current offset, e.g. a stdin partially consumed by the
+/// shell, `None` if it is not a regular file, has nothing left to read or could not be mapped
+pub fn map_file(file: &File) -> Option<Mmap> {
+    let metadata = file.metadata().ok()?;
+    let offset = (&*file).stream_position().ok()?;
+    if !metadata.is_file() || metadata.len() <= offset {
+        return None;
+    }
+
+    // Safety: the behavior is undefined if the file is truncated by others while mapped,
+    // the same caveat every tool mapping its input has.
+    unsafe { MmapOptions::new().offset(offset).map(file) }.ok()
+}
+
+/// split `bytes` into segments of about `size` bytes, each ends at a line boundary
+pub fn split_segments(bytes: &[u8], line_ending: u8, size: usize) -> Vec<(usize, usize)> {
+    let mut segments = Vec::new();
+    let mut start = 0;
+
+    while start < bytes.len() {
+        let mut end = std::cmp::min(start + size, bytes.len());
+        if end < bytes.len() {
+            // extend to the end of the line that `end` falls into
+            end = bytes[end - 1..]
+                .iter()
+                .position(|&b| b == line_ending)
+                .map(|pos| end + pos)
+                .unwrap_or(bytes.len());
+        }
+        segments.push((start, end));
+        start = end;
+    }
+
+    segments
+}
+
+/// ranges of the lines in `bytes`, without the line endings (`\r\n` is also stripped for `\n`).
+/// the same as `str::lines`, a trailing line ending does not start a new line.
+pub fn line_ranges(bytes: &[u8], line_ending: u8) -> impl Iterator<Item = (usize, usize)> + '_ {
+    let mut start = 0;
+
+    std::iter::from_fn(move || {
+        if start >= bytes.len() {
+            return None;
+        }
+
+        let line_start = start;
+        let mut line_end = bytes[start..]
+            .iter()
+            .position(|&b| b == line_ending)
+            .map(|pos| start + pos)
+            .unwrap_or(bytes.len());
+        start = line_end + 1;
+
+        if line_ending == b'\n' && line_end > line_start && bytes[line_end - 1] == b'\r' {
+            line_end -= 1;
+        }
+
+        Some((line_start, line_end))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(bytes: &[u8], line_ending: u8) -> Vec<&[u8]> {
+        line_ranges(bytes, line_ending)
+            .map(|(start, end)| &bytes[start..end])
+            .collect()
+    }
+
+    #[test]
+    fn test_line_ranges() {
+        let empty: Vec<&[u8]> = vec![];
+        assert_eq!(lines(b"", b'\n'), empty);
+        assert_eq!(lines(b"a\nbc\n", b'\n'), vec![&b"a"[..], b"bc"]);
+        assert_eq!(lines(b"a\r\n\nbc", b'\n'), vec![&b"a"[..], b"", b"bc"]);
+        assert_eq!(lines(b"a\nb\0c\0", b'\0'), vec![&b"a\nb"[..], b"c"]);
+    }
+
+    #[test]
+    fn test_split_segments() {
+        let bytes = b"aaa\nbb\nc\ndddd\n";
+        assert_eq!(split_segments(bytes, b'\n', 2), vec![(0, 4), (4, 7), (7, 9), (9, 14)]);
+        assert_eq!(split_segments(bytes, b'\n', 6), vec![(0, 7), (7, 14)]);
+        assert_eq!(split_segments(bytes, b'\n', 100), vec![(0, 14)]);
+        assert_eq!(split_segments(b"aaaa", b'\n', 2), vec![(0, 4)]);
+    }
+
+    #[test]
+    fn test_map_file_from_offset() {
+        use std::io::SeekFrom;
+
+        let path = std::env::temp_dir().join(format!("sk-test-map-file-{}", std::process::id()));
+        std::fs::write(&path, "skipped\nkept\n").unwrap();
+        let mut file = File::open(&path).unwrap();
+        file.seek(SeekFrom::Start(8)).unwrap();
+        let map = map_file(&file);
+        file.seek(SeekFrom::End(0)).unwrap();
+        let empty = map_file(&file);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(&map.unwrap()[..], b"kept\n");
+        assert!(empty.is_none());
+    }
+}
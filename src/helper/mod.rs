@@ -1,4 +1,6 @@
+pub mod arena;
 pub mod ingest;
 pub mod item;
 pub mod item_reader;
+pub mod mmap;
 pub mod selector;
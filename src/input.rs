@@ -1,6 +1,7 @@
 ///! Input will listens to user input, modify the query string, send special
 ///! keystrokes(such as Enter, Ctrl-p, Ctrl-n, etc) to the controller.
-use crate::event::{parse_event, Event};
+use crate::event::{action_name, parse_event, Event};
+use crate::SkimOptions;
 use regex::Regex;
 use std::collections::HashMap;
 use std::sync::LazyLock;
@@ -20,6 +21,28 @@ impl Input {
         }
     }
 
+    pub fn with_options(options: &SkimOptions) -> Self {
+        let mut input = Self::new();
+        input.parse_keymaps(&options.bind);
+        input.parse_expect_keys(options.expect.as_deref());
+        input
+    }
+
+    /// the keys and their action chains in the form of `--bind`, ordered by the actions
+    pub fn bindings(&self) -> Vec<(String, String)> {
+        let mut bindings: Vec<(String, String)> = self
+            .keymap
+            .iter()
+            .filter(|(&key, _)| key != Key::Null)
+            .map(|(&key, action_chain)| {
+                let actions: Vec<String> = action_chain.iter().filter_map(action_name).collect();
+                (key_name(key), actions.join("+"))
+            })
+            .collect();
+        bindings.sort_by(|(key_a, actions_a), (key_b, actions_b)| actions_a.cmp(actions_b).then(key_a.cmp(key_b)));
+        bindings
+    }
+
     pub fn translate_event(&self, event: TermEvent) -> (Key, ActionChain) {
         match event {
             // search event from keymap
@@ -164,6 +187,7 @@ fn get_default_key_map() -> HashMap<Key, ActionChain> {
     ret.insert(Key::Down,         vec![Event::EvActDown(1)]);
     ret.insert(Key::Ctrl('e'),    vec![Event::EvActEndOfLine]);
     ret.insert(Key::End,          vec![Event::EvActEndOfLine]);
+    ret.insert(Key::F(1),         vec![Event::EvActShowHelp]);
     ret.insert(Key::Ctrl('f'),    vec![Event::EvActForwardChar]);
     ret.insert(Key::Right,        vec![Event::EvActForwardChar]);
     ret.insert(Key::Alt('f'),     vec![Event::EvActForwardWord]);
@@ -190,6 +214,62 @@ fn get_default_key_map() -> HashMap<Key, ActionChain> {
     ret
 }
 
+/// the name of the key in the form of `--bind`, e.g. `ctrl-a`, `shift-tab`
+#[rustfmt::skip]
+fn key_name(key: Key) -> String {
+    let name = match key {
+        Key::ESC           => "esc",
+        Key::Ctrl(' ')     => "ctrl-space",
+        Key::Ctrl(ch)      => return format!("ctrl-{}", ch),
+        Key::CtrlAlt(ch)   => return format!("ctrl-alt-{}", ch),
+        Key::Alt(ch) if ch.is_ascii_uppercase() => return format!("alt-shift-{}", ch.to_ascii_lowercase()),
+        Key::Alt(ch)       => return format!("alt-{}", ch),
+        Key::Char(' ')     => "space",
+        Key::Char(ch)      => return ch.to_string(),
+        Key::F(n)          => return format!("f{}", n),
+        Key::Tab           => "tab",
+        Key::Enter         => "enter",
+        Key::BackTab       => "shift-tab",
+        Key::Backspace     => "bspace",
+        Key::AltBackTab    => "alt-shift-tab",
+        Key::AltBackspace  => "alt-bspace",
+        Key::AltEnter      => "alt-enter",
+        Key::AltTab        => "alt-tab",
+        Key::Up            => "up",
+        Key::Down          => "down",
+        Key::Left          => "left",
+        Key::Right         => "right",
+        Key::Home          => "home",
+        Key::End           => "end",
+        Key::Insert        => "insert",
+        Key::Delete        => "del",
+        Key::PageUp        => "page-up",
+        Key::PageDown      => "page-down",
+        Key::CtrlUp        => "ctrl-up",
+        Key::CtrlDown      => "ctrl-down",
+        Key::CtrlLeft      => "ctrl-left",
+        Key::CtrlRight     => "ctrl-right",
+        Key::ShiftUp       => "shift-up",
+        Key::ShiftDown     => "shift-down",
+        Key::ShiftLeft     => "shift-left",
+        Key::ShiftRight    => "shift-right",
+        Key::AltUp         => "alt-up",
+        Key::AltDown       => "alt-down",
+        Key::AltLeft       => "alt-left",
+        Key::AltRight      => "alt-right",
+        Key::AltHome       => "alt-home",
+        Key::AltEnd        => "alt-end",
+        Key::AltPageUp     => "alt-page-up",
+        Key::AltPageDown   => "alt-page-down",
+        Key::AltShiftUp    => "alt-shift-up",
+        Key::AltShiftDown  => "alt-shift-down",
+        Key::AltShiftLeft  => "alt-shift-left",
+        Key::AltShiftRight => "alt-shift-right",
+        _ => return format!("{:?}", key).to_lowercase(),
+    };
+    name.to_string()
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -246,4 +326,26 @@ mod test {
             key_action[1]
         );
     }
+
+    #[test]
+    fn bindings_should_be_readable() {
+        let mut input = Input::new();
+        input.parse_keymap("ctrl-x:toggle+down(2),alt-shift-k:execute(less {}),?:show-help");
+        let bindings = input.bindings();
+        let find = |key: &str| {
+            bindings
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, actions)| actions.as_str())
+        };
+
+        assert_eq!(find("ctrl-x"), Some("toggle+down(2)"));
+        assert_eq!(find("alt-shift-k"), Some("execute(less {})"));
+        assert_eq!(find("?"), Some("show-help"));
+        assert_eq!(find("f1"), Some("show-help"));
+        assert_eq!(find("shift-tab"), Some("toggle+up"));
+        for (key, _) in bindings.iter() {
+            assert_eq!(from_keyname(key).map(key_name).as_ref(), Some(key));
+        }
+    }
 }
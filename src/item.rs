@@ -77,9 +77,11 @@ impl MatchedItem {
 
 use std::cmp::Ordering as CmpOrd;
 
+// Items of the same rank are ordered by their index, so that the final order doesn't depend on
+// the order in which the (partial) results of the matcher arrive.
 impl PartialEq for MatchedItem {
     fn eq(&self, other: &Self) -> bool {
-        self.rank.eq(&other.rank)
+        self.rank.eq(&other.rank) && self.item_idx.eq(&other.item_idx)
     }
 }
 
@@ -87,13 +89,13 @@ impl std::cmp::Eq for MatchedItem {}
 
 impl PartialOrd for MatchedItem {
     fn partial_cmp(&self, other: &Self) -> Option<CmpOrd> {
-        self.rank.partial_cmp(&other.rank)
+        Some(self.cmp(other))
     }
 }
 
 impl Ord for MatchedItem {
     fn cmp(&self, other: &Self) -> CmpOrd {
-        self.rank.cmp(&other.rank)
+        self.rank.cmp(&other.rank).then(self.item_idx.cmp(&other.item_idx))
     }
 }
 
@@ -11,6 +11,7 @@ use crossbeam_channel::{unbounded, Receiver, Sender};
 use tuikit::prelude::{Event as TermEvent, *};
 
 pub use crate::ansi::AnsiString;
+pub use crate::border::BorderStyle;
 pub use crate::engine::fuzzy::FuzzyAlgorithm;
 use crate::event::{EventReceiver, EventSender};
 use crate::model::Model;
@@ -18,6 +19,8 @@ pub use crate::options::SkimOptions;
 pub use crate::output::SkimOutput;
 pub use crate::reader::CommandCollector;
 use crate::reader::Reader;
+pub use crate::theme::ColorTheme;
+pub use crate::wait_group::WaitGroup;
 
 #[cfg(feature = "malloc_trim")]
 #[cfg(target_os = "linux")]
@@ -25,11 +28,14 @@ use crate::reader::Reader;
 use libc as raw_libc;
 
 mod ansi;
+mod border;
 mod engine;
 mod event;
 pub mod field;
 mod global;
+mod group;
 mod header;
+mod help;
 mod helper;
 mod input;
 mod item;
@@ -42,10 +48,14 @@ pub mod prelude;
 mod previewer;
 mod query;
 mod reader;
+mod scrollbar;
 mod selection;
 mod spinlock;
+mod tabular;
 mod theme;
+mod tree;
 mod util;
+mod wait_group;
 
 //------------------------------------------------------------------------------
 pub trait AsAny {
@@ -119,6 +129,19 @@ pub trait SkimItem: Send + Sync {
     fn get_matching_ranges(&self) -> Option<&[(usize, usize)]> {
         None
     }
+
+    /// The group of the item, items of the same group are listed under its header when the
+    /// `groups` option is set. Default to `None`, items without a group are listed first.
+    fn group(&self) -> Option<Cow<str>> {
+        None
+    }
+
+    /// Secondary information (e.g. a size or a description) displayed at the right of the item
+    /// with the `annotation` color. It is not matched, nor scrolled with the text, which is
+    /// truncated to make room for it.
+    fn annotation(&self) -> Option<Cow<str>> {
+        None
+    }
 }
 
 //------------------------------------------------------------------------------
@@ -144,11 +167,36 @@ pub struct DisplayContext<'a> {
     pub matches: Option<Matches<'a>>,
     pub container_width: usize,
     pub highlight_attr: Attr,
+    /// the index of the query term of each char in `Matches::CharIndices`
+    pub matched_terms: Option<&'a [u8]>,
+    /// the highlights of the query terms in order, reused for the terms beyond them. Empty to
+    /// highlight all the terms with `highlight_attr`
+    pub highlight_palette: &'a [Attr],
+}
+
+impl<'a> DisplayContext<'a> {
+    /// the highlight of the `nth` char of `Matches::CharIndices`, by the query term matching it
+    pub fn char_highlight(&self, nth: usize) -> Attr {
+        match self.matched_terms.and_then(|terms| terms.get(nth)) {
+            Some(&term) if !self.highlight_palette.is_empty() => {
+                self.highlight_palette[term as usize % self.highlight_palette.len()]
+            }
+            _ => self.highlight_attr,
+        }
+    }
 }
 
 impl<'a> From<DisplayContext<'a>> for AnsiString {
     fn from(context: DisplayContext<'a>) -> Self {
         match context.matches {
+            Some(Matches::CharIndices(indices)) if context.matched_terms.is_some() => {
+                let fragments = indices
+                    .iter()
+                    .enumerate()
+                    .map(|(nth, &idx)| (context.char_highlight(nth), (idx as u32, idx as u32 + 1)))
+                    .collect();
+                AnsiString::new_str(context.text, fragments)
+            }
             Some(Matches::CharIndices(indices)) => AnsiString::from((context.text, indices, context.highlight_attr)),
             Some(Matches::CharRange(start, end)) => {
                 AnsiString::new_str(context.text, vec![(context.highlight_attr, (start as u32, end as u32))])
@@ -222,7 +270,35 @@ pub enum CaseMatching {
 pub enum MatchRange {
     ByteRange(usize, usize),
     // range of bytes
-    Chars(Box<[usize]>), // individual character indices matched
+    Chars(Box<[usize]>),       // individual character indices matched
+    TermChars(Box<TermChars>), // individual character indices matched by the terms of a query
+}
+
+/// the chars matched by a query of several terms, boxed so that the other ranges don't pay for it
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct TermChars {
+    pub indices: Box<[usize]>,
+    /// the index of the query term that matched each char, the terms beyond 255 share the last one
+    pub terms: Box<[u8]>,
+}
+
+impl MatchRange {
+    /// the individual char indices matched, if any
+    pub fn char_indices(&self) -> Option<&[usize]> {
+        match self {
+            MatchRange::ByteRange(..) => None,
+            MatchRange::Chars(indices) => Some(indices),
+            MatchRange::TermChars(chars) => Some(&chars.indices),
+        }
+    }
+
+    /// the query term of each char of `char_indices`, for queries of several terms
+    pub fn terms(&self) -> Option<&[u8]> {
+        match self {
+            MatchRange::TermChars(chars) => Some(&chars.terms),
+            _ => None,
+        }
+    }
 }
 
 pub type Rank = [i32; 4];
@@ -242,6 +318,7 @@ impl MatchResult {
                 (first..last).collect()
             }
             MatchRange::Chars(vec) => vec.clone().into(),
+            MatchRange::TermChars(chars) => chars.indices.clone().into(),
         }
     }
 }
@@ -278,9 +355,14 @@ impl Skim {
     ///   If None is given, skim will invoke the command given to fetch the items.
     ///
     /// return:
-    /// - None: on internal errors.
+    /// - None: on internal errors, or invalid options (see `SkimOptions::validate`).
     /// - SkimOutput: the collected key, event, query, selected items, etc.
     pub fn run_with(options: &SkimOptions, source: Option<SkimItemReceiver>) -> Option<SkimOutput> {
+        if let Err(err) = options.validate() {
+            error!("{}", err);
+            return None;
+        }
+
         let min_height = options
             .min_height
             .map(Skim::parse_height_string)
@@ -290,6 +372,8 @@ impl Skim {
             .map(Skim::parse_height_string)
             .expect("height should have default values");
 
+        ColorTheme::detect_background(options.color);
+
         let (tx, rx): (EventSender, EventReceiver) = unbounded();
         let term = Arc::new(
             Term::with_options(
@@ -309,9 +393,8 @@ impl Skim {
 
         //------------------------------------------------------------------------------
         // input
-        let mut input = input::Input::new();
-        input.parse_keymaps(&options.bind);
-        input.parse_expect_keys(options.expect.as_deref());
+        let input = input::Input::with_options(options);
+        let bindings = input.bindings();
 
         let tx_clone = tx.clone();
         let term_clone = term.clone();
@@ -335,7 +418,7 @@ impl Skim {
 
         //------------------------------------------------------------------------------
         // model + previewer
-        let mut model = Model::new(rx, tx, reader, term.clone(), options);
+        let mut model = Model::new(rx, tx, reader, term.clone(), options, bindings);
         let ret = model.start();
         let _ = term.send_event(TermEvent::User(())); // interrupt the input thread
         let _ = input_thread.join();
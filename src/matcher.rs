@@ -23,6 +23,9 @@ use crate::malloc_trim;
 
 const UNMATCHED_RANK: Rank = [0i32, 0i32, 0i32, 0i32];
 const UNMATCHED_RANGE: Option<MatchRange> = None;
+const CHUNK_SIZE: usize = 4096;
+// number of best items of every chunk that are published before the matcher stops
+const PARTIAL_TOP_K: usize = 100;
 
 pub static THREAD_POOL: LazyLock<Arc<ThreadPool>> = LazyLock::new(|| {
     let cpus: usize = num_cpus::get();
@@ -43,6 +46,7 @@ pub struct MatcherControl {
     processed: Arc<AtomicUsize>,
     matched: Arc<AtomicUsize>,
     items: Arc<SpinLock<Vec<MatchedItem>>>,
+    partial_items: Arc<SpinLock<Vec<MatchedItem>>>,
     opt_thread_handle: Option<JoinHandle<()>>,
 }
 
@@ -80,16 +84,37 @@ impl MatcherControl {
         std::mem::take(&mut *items)
     }
 
+    /// take the partial results published by the (maybe still running) matcher.
+    /// Items returned here will not be returned again by `into_items`.
+    pub fn take_partial(&mut self) -> Vec<MatchedItem> {
+        let mut items = self.partial_items.lock();
+        std::mem::take(&mut *items)
+    }
+
     pub fn stopped(&self) -> bool {
         self.stopped.load(Ordering::Relaxed)
     }
 
+    /// block until the matcher thread finishes
+    pub fn wait(&mut self) {
+        if let Some(handle) = self.opt_thread_handle.take() {
+            let _ = handle.join();
+        }
+    }
+
+    /// wait for the matcher to stop, and take all the items that are not taken by `take_partial`
     #[allow(clippy::wrong_self_convention)]
     pub fn into_items(&mut self) -> Vec<MatchedItem> {
-        while !self.stopped.load(Ordering::Relaxed) {}
+        self.wait();
+        let mut partial = self.take_partial();
         let mut locked = self.items.lock();
 
-        std::mem::take(&mut *locked)
+        if partial.is_empty() {
+            std::mem::take(&mut *locked)
+        } else {
+            partial.append(&mut locked);
+            partial
+        }
     }
 }
 
@@ -97,6 +122,8 @@ impl MatcherControl {
 pub struct Matcher {
     engine_factory: Rc<dyn MatchEngineFactory>,
     case_matching: CaseMatching,
+    stream: bool,
+    tac: bool,
 }
 
 impl Matcher {
@@ -104,6 +131,8 @@ impl Matcher {
         Self {
             engine_factory,
             case_matching: CaseMatching::default(),
+            stream: true,
+            tac: false,
         }
     }
 
@@ -116,6 +145,26 @@ impl Matcher {
         self
     }
 
+    pub fn get_stream(&self) -> bool {
+        self.stream
+    }
+
+    /// publish the best items of every chunk while the matcher is still running
+    pub fn set_stream(mut self, stream: bool) -> Self {
+        self.stream = stream;
+        self
+    }
+
+    pub fn get_tac(&self) -> bool {
+        self.tac
+    }
+
+    /// with `tac`, the items with greater rank are shown first, thus considered "best"
+    pub fn set_tac(mut self, tac: bool) -> Self {
+        self.tac = tac;
+        self
+    }
+
     pub fn build(self) -> Self {
         self
     }
@@ -138,6 +187,10 @@ impl Matcher {
         let matched_clone = matched.clone();
         let matched_items = Arc::new(SpinLock::new(matched_items));
         let matched_items_weak = Arc::downgrade(&matched_items);
+        let partial_items = Arc::new(SpinLock::new(Vec::new()));
+        let partial_items_clone = partial_items.clone();
+        let stream = self.stream;
+        let tac = self.tac;
 
         // shortcut for when there is no query or query is disabled
         let matcher_disabled: bool = disabled || query.is_empty();
@@ -150,6 +203,7 @@ impl Matcher {
                     let stopped_ref = stopped.as_ref();
                     let processed_ref = processed.as_ref();
                     let matched_ref = matched.as_ref();
+                    let partial_ref = partial_items_clone.as_ref();
 
                     trace!("matcher start, total: {}", items.len());
 
@@ -157,7 +211,7 @@ impl Matcher {
                         let par_iter = items
                             .par_iter()
                             .enumerate()
-                            .chunks(4096)
+                            .chunks(CHUNK_SIZE)
                             .take_any_while(|vec| {
                                 if stopped_ref.load(Ordering::Relaxed) {
                                     return false;
@@ -166,21 +220,33 @@ impl Matcher {
                                 processed_ref.fetch_add(vec.len(), Ordering::Relaxed);
                                 true
                             })
-                            .flatten()
-                            .filter_map(|(index, item)| {
-                                // dummy values should not change, as changing them
-                                // may cause the disabled/query empty case disappear!
-                                // especially item index.  Needs an index to appear!
-                                if matcher_disabled {
-                                    return Some(MatchedItem {
-                                        item: Arc::downgrade(item),
-                                        rank: UNMATCHED_RANK,
-                                        matched_range: UNMATCHED_RANGE,
-                                        item_idx: (num_taken + index) as u32,
-                                    });
+                            .flat_map_iter(|chunk| {
+                                let mut matched_chunk: Vec<MatchedItem> = chunk
+                                    .into_iter()
+                                    .filter_map(|(index, item)| {
+                                        // dummy values should not change, as changing them
+                                        // may cause the disabled/query empty case disappear!
+                                        // especially item index.  Needs an index to appear!
+                                        if matcher_disabled {
+                                            return Some(MatchedItem {
+                                                item: Arc::downgrade(item),
+                                                rank: UNMATCHED_RANK,
+                                                matched_range: UNMATCHED_RANGE,
+                                                item_idx: (num_taken + index) as u32,
+                                            });
+                                        }
+
+                                        Self::process_item(index, num_taken, matched_ref, matcher_engine.as_ref(), item)
+                                    })
+                                    .collect();
+
+                                // publish the best ones, so that they could be shown before the matcher stops
+                                if stream && !stopped_ref.load(Ordering::Relaxed) {
+                                    let best = split_best(&mut matched_chunk, PARTIAL_TOP_K, tac);
+                                    partial_ref.lock().extend(best);
                                 }
 
-                                Self::process_item(index, num_taken, matched_ref, matcher_engine.as_ref(), item)
+                                matched_chunk
                             });
 
                         if !stopped_ref.load(Ordering::Relaxed) {
@@ -202,6 +268,7 @@ impl Matcher {
             matched: matched_clone,
             processed: processed_clone,
             items: matched_items,
+            partial_items,
             opt_thread_handle: Some(matcher_handle),
         }
     }
@@ -225,3 +292,61 @@ impl Matcher {
         })
     }
 }
+
+/// move the best `k` items out of `items`, the rest are left in `items` (in no particular order)
+fn split_best(items: &mut Vec<MatchedItem>, k: usize, tac: bool) -> Vec<MatchedItem> {
+    if items.len() <= k {
+        return std::mem::take(items);
+    }
+
+    if tac {
+        items.select_nth_unstable_by(k, |a, b| b.cmp(a));
+    } else {
+        items.select_nth_unstable(k);
+    }
+
+    let rest = items.split_off(k);
+    std::mem::replace(items, rest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matched_items(ranks: &[i32]) -> Vec<MatchedItem> {
+        ranks
+            .iter()
+            .enumerate()
+            .map(|(idx, &rank)| MatchedItem {
+                item: Weak::<String>::new(),
+                rank: [rank, 0, 0, 0],
+                matched_range: None,
+                item_idx: idx as u32,
+            })
+            .collect()
+    }
+
+    fn sorted_ranks(items: &[MatchedItem]) -> Vec<i32> {
+        let mut ranks: Vec<i32> = items.iter().map(|item| item.rank[0]).collect();
+        ranks.sort();
+        ranks
+    }
+
+    #[test]
+    fn test_split_best() {
+        let mut items = matched_items(&[5, 3, 9, 1, 7, 2]);
+        let best = split_best(&mut items, 3, false);
+        assert_eq!(sorted_ranks(&best), vec![1, 2, 3]);
+        assert_eq!(sorted_ranks(&items), vec![5, 7, 9]);
+
+        let mut items = matched_items(&[5, 3, 9, 1, 7, 2]);
+        let best = split_best(&mut items, 2, true);
+        assert_eq!(sorted_ranks(&best), vec![7, 9]);
+        assert_eq!(sorted_ranks(&items), vec![1, 2, 3, 5]);
+
+        let mut items = matched_items(&[5, 3]);
+        let best = split_best(&mut items, 3, false);
+        assert_eq!(sorted_ranks(&best), vec![3, 5]);
+        assert!(items.is_empty());
+    }
+}
@@ -10,11 +10,14 @@ use std::time::{Duration, Instant};
 use regex::Regex;
 use std::sync::LazyLock;
 use tuikit::prelude::{Event as TermEvent, *};
+use unicode_width::UnicodeWidthStr;
 
+use crate::border::{Border, BorderLabel, BorderStyle};
 use crate::engine::factory::{AndOrEngineFactory, ExactOrFuzzyEngineFactory, RegexEngineFactory};
 use crate::event::{Event, EventHandler, EventReceiver, EventSender};
 use crate::global::current_run_num;
 use crate::header::Header;
+use crate::help::Help;
 use crate::input::parse_action_arg;
 use crate::item::{parse_criteria, ItemPool, MatchedItem, RankBuilder, RankCriteria};
 use crate::matcher::{Matcher, MatcherControl};
@@ -23,11 +26,14 @@ use crate::output::SkimOutput;
 use crate::previewer::Previewer;
 use crate::query::Query;
 use crate::reader::{Reader, ReaderControl};
+use crate::scrollbar::DEFAULT_SCROLLBAR;
 use crate::selection::Selection;
 use crate::spinlock::SpinLock;
 use crate::theme::ColorTheme;
 use crate::util::clear_canvas;
-use crate::util::{depends_on_items, inject_command, margin_string_to_size, parse_margin, InjectContext};
+use crate::util::{
+    depends_on_items, inject_command, margin_string_to_size, parse_duration, parse_margin, InjectContext,
+};
 use crate::{MatchEngineFactory, MatchRange, SkimItem};
 use std::cmp::max;
 
@@ -54,6 +60,8 @@ pub struct Model {
     query: Query,
     selection: Selection,
     num_options: usize,
+    // number of partial results of the running matcher that are already in selection
+    num_partial: usize,
     select1: bool,
     exit0: bool,
     sync: bool,
@@ -77,11 +85,20 @@ pub struct Model {
     matcher_control: Option<MatcherControl>,
 
     header: Header,
+    help: Help,
+
+    border: Option<Border>,
+    list_border: Option<Border>,
+    header_border: Option<Border>,
+    preview_border: Option<Border>,
 
     preview_hidden: bool,
     previewer: Option<Previewer>,
     preview_direction: Direction,
     preview_size: Size,
+    preview_window: String,
+    // the layouts of the last `change-preview-window` and the index of the next one
+    preview_layouts: Option<(String, usize)>,
 
     margin_top: Size,
     margin_right: Size,
@@ -90,7 +107,8 @@ pub struct Model {
 
     layout: String,
     delimiter: Regex,
-    inline_info: bool,
+    info: InfoStyle,
+    info_format: Option<String>,
     no_clear_if_empty: bool,
     theme: Arc<ColorTheme>,
 
@@ -121,7 +139,14 @@ impl Drop for Model {
 }
 
 impl Model {
-    pub fn new(rx: EventReceiver, tx: EventSender, reader: Reader, term: Arc<Term>, options: &SkimOptions) -> Self {
+    pub fn new(
+        rx: EventReceiver,
+        tx: EventSender,
+        reader: Reader,
+        term: Arc<Term>,
+        options: &SkimOptions,
+        bindings: Vec<(String, String)>,
+    ) -> Self {
         let default_command = match env::var("SKIM_DEFAULT_COMMAND").as_ref().map(String::as_ref) {
             Ok("") | Err(_) => "find .".to_owned(),
             Ok(val) => val.to_owned(),
@@ -146,14 +171,21 @@ impl Model {
         let selection = Selection::with_options(options).theme(theme.clone());
         let regex_engine: Rc<dyn MatchEngineFactory> =
             Rc::new(RegexEngineFactory::builder().rank_builder(rank_builder.clone()).build());
-        let regex_matcher = Matcher::builder(regex_engine).build();
+        let regex_matcher = Matcher::builder(regex_engine)
+            .set_stream(!options.nosort)
+            .set_tac(options.tac)
+            .build();
 
         let exact_mode = options.exact;
         let case_matching = options.case;
 
         let matcher = if let Some(engine_factory) = options.engine_factory.as_ref() {
             // use provided engine
-            Matcher::builder(engine_factory.clone()).set_case(options.case).build()
+            Matcher::builder(engine_factory.clone())
+                .set_case(options.case)
+                .set_stream(!options.nosort)
+                .set_tac(options.tac)
+                .build()
         } else {
             let fuzzy_engine_factory: Rc<dyn MatchEngineFactory> = Rc::new(AndOrEngineFactory::new(Box::new(
                 ExactOrFuzzyEngineFactory::builder()
@@ -162,7 +194,11 @@ impl Model {
                     .rank_builder(rank_builder.clone())
                     .build(),
             )));
-            Matcher::builder(fuzzy_engine_factory).set_case(case_matching).build()
+            Matcher::builder(fuzzy_engine_factory)
+                .set_case(case_matching)
+                .set_stream(!options.nosort)
+                .set_tac(options.tac)
+                .build()
         };
 
         let item_pool = Arc::new(ItemPool::new().lines_to_reserve(options.header_lines));
@@ -171,6 +207,8 @@ impl Model {
             .item_pool(&item_pool)
             .theme(theme.clone());
 
+        let help = Help::with_options(options, bindings).theme(theme.clone());
+
         let margins = options
             .margin
             .map(parse_margin)
@@ -182,6 +220,7 @@ impl Model {
             query,
             selection,
             num_options: 0,
+            num_partial: 0,
             select1: false,
             exit0: false,
             sync: false,
@@ -201,10 +240,17 @@ impl Model {
             matcher_control: None,
 
             header,
+            help,
+            border: None,
+            list_border: None,
+            header_border: None,
+            preview_border: None,
             preview_hidden: true,
             previewer: None,
             preview_direction: Direction::Right,
             preview_size: Size::Default,
+            preview_window: "".to_string(),
+            preview_layouts: None,
 
             margin_top,
             margin_right,
@@ -213,7 +259,8 @@ impl Model {
 
             layout: "default".to_string(),
             delimiter: Regex::new(DELIMITER_STR).unwrap(),
-            inline_info: false,
+            info: InfoStyle::Default,
+            info_format: None,
             no_clear_if_empty: false,
             theme,
             rank_builder,
@@ -230,21 +277,45 @@ impl Model {
         self.layout = options.layout.to_string();
 
         if options.inline_info {
-            self.inline_info = true;
+            self.info = InfoStyle::Inline;
         }
 
+        // `SkimOptions::validate` rejects the unknown styles
+        if let Some(Ok(info)) = options.info.map(InfoStyle::parse) {
+            self.info = info;
+        }
+
+        self.info_format = options.info_format.map(|format| format.to_string());
+
         if options.regex {
             self.use_regex = true;
         }
 
+        let theme = &self.theme;
+        self.border = Border::from_options(options.border, options.border_label, options.border_label_pos, theme);
+        self.list_border = Border::from_options(options.list_border, options.list_label, options.list_label_pos, theme);
+        self.header_border = Border::from_options(
+            options.header_border,
+            options.header_label,
+            options.header_label_pos,
+            theme,
+        );
+        self.preview_border = Border::from_options(
+            options.preview_border,
+            options.preview_label,
+            options.preview_label_pos,
+            theme,
+        );
+
         // preview related
-        let (preview_direction, preview_size, preview_wrap, preview_shown) = options
+        let (preview_direction, preview_size, preview_wrap, preview_shown, preview_follow) = options
             .preview_window
             .map(Self::parse_preview)
             .expect("option 'preview-window' should be set (by default)");
         self.preview_direction = preview_direction;
         self.preview_size = preview_size;
         self.preview_hidden = !preview_shown;
+        self.preview_window = options.preview_window.unwrap_or_default().to_string();
 
         if let Some(preview_cmd) = options.preview {
             let tx = Arc::new(SpinLock::new(self.tx.clone()));
@@ -255,6 +326,14 @@ impl Model {
                 }),
             )
             .wrap(preview_wrap)
+            .follow(preview_follow)
+            .interval(options.preview_interval.and_then(parse_duration))
+            .cache_size(options.preview_cache_size)
+            .scrollbar(options.scrollbar.map(|glyphs| {
+                // the glyph of the list is used if there is no one for the preview window
+                let glyph = glyphs.chars().nth(1).or_else(|| glyphs.chars().next());
+                (glyph.unwrap_or(DEFAULT_SCROLLBAR), self.theme.preview_scrollbar())
+            }))
             .delimiter(self.delimiter.clone())
             .preview_offset(
                 options
@@ -274,13 +353,14 @@ impl Model {
         self.no_clear_if_empty = options.no_clear_if_empty;
     }
 
-    // -> (direction, size, wrap, shown)
-    fn parse_preview(preview_option: &str) -> (Direction, Size, bool, bool) {
+    // -> (direction, size, wrap, shown, follow)
+    fn parse_preview(preview_option: &str) -> (Direction, Size, bool, bool, bool) {
         let options = preview_option.split(':').collect::<Vec<&str>>();
 
         let mut direction = Direction::Right;
         let mut shown = true;
         let mut wrap = false;
+        let mut follow = false;
         let mut size = Size::Percent(50);
 
         for option in options {
@@ -302,12 +382,61 @@ impl Model {
                     "RIGHT" => direction = Direction::Right,
                     "HIDDEN" => shown = false,
                     "WRAP" => wrap = true,
+                    "NOWRAP" => wrap = false,
+                    "FOLLOW" => follow = true,
+                    "NOFOLLOW" => follow = false,
                     _ => {}
                 }
             }
         }
 
-        (direction, size, wrap, shown)
+        (direction, size, wrap, shown, follow)
+    }
+
+    // the layout is applied on top of `--preview-window`, e.g. `down:40%`
+    // -> (direction, size, wrap, shown, follow)
+    fn parse_preview_layout(preview_window: &str, layout: &str) -> (Direction, Size, bool, bool, bool) {
+        let base = preview_window
+            .split(':')
+            .filter(|option| !option.eq_ignore_ascii_case("hidden"))
+            .collect::<Vec<&str>>()
+            .join(":");
+        Self::parse_preview(&format!("{}:{}", base, layout.replace(',', ":")))
+    }
+
+    fn resize_preview(size: Size, step: i32) -> Size {
+        match size {
+            Size::Percent(percent) => Size::Percent((percent as i32 + step).clamp(1, 99) as usize),
+            Size::Fixed(fixed) => Size::Fixed(max(1, fixed as i32 + step) as usize),
+            Size::Default => Size::Default,
+        }
+    }
+
+    // cycle through the layouts separated by `|`, e.g. `right:50%|down:40%|hidden`
+    fn act_change_preview_window(&mut self, layouts: &str) {
+        let index = match self.preview_layouts.take() {
+            Some((last_layouts, index)) if last_layouts == layouts => index,
+            _ => 0,
+        };
+        let candidates = layouts.split('|').collect::<Vec<&str>>();
+        let (direction, size, wrap, shown, follow) =
+            Self::parse_preview_layout(&self.preview_window, candidates[index]);
+        self.preview_layouts = Some((layouts.to_string(), (index + 1) % candidates.len()));
+
+        self.preview_direction = direction;
+        self.preview_size = size;
+        self.preview_hidden = !shown;
+        if let Some(previewer) = self.previewer.as_mut() {
+            previewer.set_wrap(wrap);
+            previewer.set_follow(follow);
+        }
+    }
+
+    // the previewer gets its new size on drawing, so draw first and re-run the preview command
+    // to pass the new `COLUMNS` and `LINES`
+    fn on_preview_layout_change(&mut self, env: &ModelEnv) {
+        let _ = self.do_with_widget(|root| self.term.draw(&root));
+        self.draw_preview(env, true);
     }
 
     // -> string
@@ -334,21 +463,20 @@ impl Model {
             let mut ctrl = self.matcher_control.take().unwrap();
             let matched = ctrl.into_items();
 
-            match env.clear_selection {
-                ClearStrategy::DontClear => {}
-                ClearStrategy::Clear => {
-                    self.selection.clear();
-                    env.clear_selection = ClearStrategy::DontClear;
-                }
-                ClearStrategy::ClearIfNotNull => {
-                    if (!self.no_clear_if_empty && reader_stopped) || !matched.is_empty() {
-                        self.selection.clear();
-                        env.clear_selection = ClearStrategy::DontClear;
-                    }
-                }
-            };
-            self.num_options += matched.len();
+            self.clear_selection_if_needed(env, reader_stopped, matched.is_empty());
+            self.num_options += self.num_partial + matched.len();
+            self.num_partial = 0;
             self.selection.append_sorted_items(matched);
+            // the final results are shown right away
+            self.selection.sync_rows();
+        } else if let Some(ctrl) = self.matcher_control.as_mut() {
+            // show the partial results while the matcher is still running
+            let partial = ctrl.take_partial();
+            if !partial.is_empty() {
+                self.clear_selection_if_needed(env, false, false);
+                self.num_partial += partial.len();
+                self.selection.append_sorted_items(partial);
+            }
         }
 
         let items_consumed = self.item_pool.num_not_taken() == 0;
@@ -370,6 +498,22 @@ impl Model {
         }
     }
 
+    fn clear_selection_if_needed(&mut self, env: &mut ModelEnv, reader_stopped: bool, matched_is_empty: bool) {
+        match env.clear_selection {
+            ClearStrategy::DontClear => {}
+            ClearStrategy::Clear => {
+                self.selection.clear();
+                env.clear_selection = ClearStrategy::DontClear;
+            }
+            ClearStrategy::ClearIfNotNull => {
+                if (!self.no_clear_if_empty && reader_stopped) || !matched_is_empty {
+                    self.selection.clear();
+                    env.clear_selection = ClearStrategy::DontClear;
+                }
+            }
+        };
+    }
+
     fn act_rotate_mode(&mut self, env: &mut ModelEnv) {
         self.use_regex = !self.use_regex;
 
@@ -381,6 +525,7 @@ impl Model {
         env.clear_selection = ClearStrategy::Clear;
         self.item_pool.reset();
         self.num_options = 0;
+        self.num_partial = 0;
         self.restart_matcher();
     }
 
@@ -421,6 +566,7 @@ impl Model {
         env.clear_selection = ClearStrategy::ClearIfNotNull;
         self.item_pool.clear();
         self.num_options = 0;
+        self.num_partial = 0;
 
         // restart reader
         let old_reader = self.reader_control.replace(self.reader.run(&env.cmd));
@@ -441,6 +587,7 @@ impl Model {
         env.clear_selection = ClearStrategy::Clear;
         self.item_pool.reset();
         self.num_options = 0;
+        self.num_partial = 0;
         self.restart_matcher();
     }
 
@@ -536,6 +683,23 @@ impl Model {
         loop {
             let (key, ev) = next_event.take().or_else(|| self.rx.recv().ok())?;
 
+            // the help overlay takes the keys while it is shown
+            if self.help.is_visible() && key != Key::Null {
+                self.help.handle(key, &ev);
+                let _ = self.do_with_widget(|root| self.term.draw(&root));
+                let _ = self.term.present();
+                continue;
+            }
+
+            // in the jump mode, the next key picks a row by its label, any other key cancels it
+            let ev = match self.selection.jump_mode() {
+                Some(_) if key != Key::Null => {
+                    next_event = self.consume_events_of_key(key);
+                    self.selection.act_jump_key(key)
+                }
+                _ => ev,
+            };
+
             debug!("handle event: {:?}", ev);
 
             match ev {
@@ -573,6 +737,25 @@ impl Model {
                     self.preview_hidden = !self.preview_hidden;
                 }
 
+                Event::EvActChangePreviewWindow(ref layouts) => {
+                    self.act_change_preview_window(layouts);
+                    self.on_preview_layout_change(&env);
+                }
+
+                Event::EvActGrowPreview(step) => {
+                    self.preview_size = Self::resize_preview(self.preview_size, step);
+                    self.on_preview_layout_change(&env);
+                }
+
+                Event::EvActShrinkPreview(step) => {
+                    self.preview_size = Self::resize_preview(self.preview_size, -step);
+                    self.on_preview_layout_change(&env);
+                }
+
+                Event::EvActShowHelp => {
+                    self.help.show();
+                }
+
                 Event::EvActRotateMode => {
                     self.act_rotate_mode(&mut env);
                 }
@@ -631,6 +814,15 @@ impl Model {
                     });
                 }
 
+                // nothing to move in the empty query, the keys fold the tree instead
+                Event::EvActBackwardChar if self.query.in_query_mode() && env.query.is_empty() => {
+                    self.selection.act_collapse();
+                }
+
+                Event::EvActForwardChar if self.query.in_query_mode() && env.query.is_empty() => {
+                    self.selection.act_expand();
+                }
+
                 Event::EvActDeleteCharEOF => {
                     if env.in_query_mode && env.query.is_empty() || !env.in_query_mode && env.cmd_query.is_empty() {
                         next_event = Some((key, Event::EvActAbort));
@@ -672,6 +864,9 @@ impl Model {
                 }
 
                 Event::EvActRefreshPreview => {
+                    if let Some(previewer) = self.previewer.as_ref() {
+                        previewer.clear_cache();
+                    }
                     self.draw_preview(&env, true);
                 }
 
@@ -747,9 +942,23 @@ impl Model {
         rx_try_iter.next()
     }
 
+    // consume the rest of the actions bound to `key`, return the next event of others
+    fn consume_events_of_key(&self, key: Key) -> Option<(Key, Event)> {
+        let mut rx_try_iter = self.rx.try_iter().peekable();
+        while let Some((next_key, _ev)) = rx_try_iter.peek() {
+            if *next_key == key {
+                let _ = rx_try_iter.next();
+            } else {
+                break;
+            }
+        }
+        rx_try_iter.next()
+    }
+
     fn restart_matcher(&mut self) {
         self.matcher_timer = Instant::now();
         let query = self.query.get_fz_query();
+        self.selection.expand_tree(!query.is_empty());
 
         // if there are new items, move them to item pool
         let reader_ctrl = self.reader_control.as_mut();
@@ -792,8 +1001,11 @@ impl Model {
                         .rank_builder(self.rank_builder.clone())
                         .build(),
                 )));
-                let case_matching = self.matcher.get_case();
-                let fast_matcher = Matcher::builder(fuzzy_engine_factory).set_case(case_matching).build();
+                let fast_matcher = Matcher::builder(fuzzy_engine_factory)
+                    .set_case(self.matcher.get_case())
+                    .set_stream(self.matcher.get_stream())
+                    .set_tac(self.matcher.get_tac())
+                    .build();
                 self.matcher = fast_matcher;
             });
         }
@@ -858,26 +1070,45 @@ impl Model {
             time_since_match: self.matcher_timer.elapsed(),
             matcher_mode,
             theme: self.theme.clone(),
-            inline_info: self.inline_info,
+            info: self.info,
+            info_format: self.info_format.clone(),
         };
         let status_inline = status.clone();
+        let info_inline = self.info.is_inline();
 
-        let win_selection = Win::new(&self.selection);
+        let win_selection = match self.list_border.as_ref() {
+            Some(border) => border.wrap(&self.selection),
+            None => Win::new(&self.selection),
+        };
         let win_query = Win::new(&self.query)
-            .basis(if self.inline_info { 0 } else { 1 })
+            .basis(if info_inline { 0 } else { 1 })
             .grow(0)
             .shrink(0);
         let win_status = Win::new(status)
-            .basis(if self.inline_info { 0 } else { 1 })
+            .basis(if self.info == InfoStyle::Default { 1 } else { 0 })
             .grow(0)
             .shrink(0);
-        let win_header = Win::new(&self.header).grow(0).shrink(0);
+        // no border around an empty header
+        let win_header = match self.header_border.as_ref() {
+            Some(border) if !self.header.is_empty() => border.wrap(&self.header),
+            _ => Win::new(&self.header),
+        }
+        .grow(0)
+        .shrink(0);
         let win_query_status = HSplit::default()
-            .basis(if self.inline_info { 1 } else { 0 })
+            .basis(if info_inline { 1 } else { 0 })
             .grow(0)
-            .shrink(0)
-            .split(Win::new(&self.query).grow(0).shrink(0))
-            .split(Win::new(status_inline).grow(1).shrink(0));
+            .shrink(0);
+        let win_query_status = if self.info == InfoStyle::RightOfPrompt {
+            win_query_status
+                .split(Win::new(self.query.prompt_widget()).grow(0).shrink(0))
+                .split(Win::new(status_inline).grow(0).shrink(0))
+                .split(Win::new(self.query.input_widget()).grow(1))
+        } else {
+            win_query_status
+                .split(Win::new(&self.query).grow(0).shrink(0))
+                .split(Win::new(status_inline).grow(1).shrink(0))
+        };
 
         let layout = &self.layout as &str;
         let win_main = match layout {
@@ -901,20 +1132,29 @@ impl Model {
                 .split(win_query_status),
         };
 
-        let screen: Box<dyn Widget<Event>> = if !self.preview_hidden && self.previewer.is_some() {
+        let screen: Box<dyn Widget<Event>> = if self.help.is_visible() {
+            let border = Border::new(BorderStyle::Rounded)
+                .label(Some(BorderLabel::new(" Key bindings ", None)))
+                .attr(self.theme.border())
+                .label_attr(self.theme.label());
+            Box::new(border.wrap(&self.help))
+        } else if !self.preview_hidden && self.previewer.is_some() {
             let previewer = self.previewer.as_ref().unwrap();
-            let win = Win::new(previewer)
-                .basis(self.preview_size)
-                .grow(0)
-                .shrink(0)
-                .border_attr(self.theme.border());
-
-            let win_preview = match self.preview_direction {
-                Direction::Up => win.border_bottom(true),
-                Direction::Right => win.border_left(true),
-                Direction::Down => win.border_top(true),
-                Direction::Left => win.border_right(true),
-            };
+            let win_preview = match self.preview_border.as_ref() {
+                Some(border) => border.wrap(previewer),
+                None => {
+                    let win = Win::new(previewer).border_attr(self.theme.border());
+                    match self.preview_direction {
+                        Direction::Up => win.border_bottom(true),
+                        Direction::Right => win.border_left(true),
+                        Direction::Down => win.border_top(true),
+                        Direction::Left => win.border_right(true),
+                    }
+                }
+            }
+            .basis(self.preview_size)
+            .grow(0)
+            .shrink(0);
 
             match self.preview_direction {
                 Direction::Up => Box::new(VSplit::default().split(win_preview).split(win_main)),
@@ -926,6 +1166,11 @@ impl Model {
             Box::new(win_main)
         };
 
+        let screen = match self.border.as_ref() {
+            Some(border) => Box::new(border.wrap(screen)),
+            None => screen,
+        };
+
         let root = Win::new(screen)
             .margin_top(self.margin_top)
             .margin_right(self.margin_right)
@@ -959,10 +1204,97 @@ struct Status {
     time_since_match: Duration,
     matcher_mode: String,
     theme: Arc<ColorTheme>,
-    inline_info: bool,
+    info: InfoStyle,
+    info_format: Option<String>,
+}
+
+impl Status {
+    fn spinner(&self) -> (char, Attr) {
+        let spinner_set: &[char] = if self.info == InfoStyle::Inline {
+            &SPINNERS_INLINE
+        } else {
+            &SPINNERS_UNICODE
+        };
+
+        if self.reading && self.time_since_read > Duration::from_millis(50) {
+            let mills = (self.time_since_read.as_secs() * 1000) as u32 + self.time_since_read.subsec_millis();
+            let index = (mills / SPINNER_DURATION) % (spinner_set.len() as u32);
+            (spinner_set[index as usize], self.theme.spinner())
+        } else if self.info == InfoStyle::Inline {
+            ('<', self.theme.prompt())
+        } else {
+            (' ', self.theme.prompt())
+        }
+    }
+
+    fn percentage(&self) -> usize {
+        (self.processed * 100).checked_div(self.total).unwrap_or(100)
+    }
+
+    /// replace the placeholders in the info format, e.g. `{matched}/{total} [{selected}]`
+    fn expand_format(&self, format: &str) -> String {
+        format
+            .replace("{matched}", &self.matched.to_string())
+            .replace("{total}", &self.total.to_string())
+            .replace("{processed}", &self.processed.to_string())
+            .replace("{percent}", &self.percentage().to_string())
+            .replace("{selected}", &self.selected.to_string())
+            .replace("{mode}", &self.matcher_mode)
+            .replace("{current}", &self.current_item_idx.to_string())
+            .replace("{hscroll}", &self.hscroll_offset.to_string())
+    }
+
+    /// the line to be printed from the left, along with the attributes
+    fn segments(&self) -> Vec<(String, Attr)> {
+        let info_attr = self.theme.info();
+        let info_attr_bold = Attr {
+            effect: Effect::BOLD,
+            ..self.theme.info()
+        };
+
+        let mut segments = vec![];
+        // the prompt is already separated from what follows it
+        if matches!(self.info, InfoStyle::Inline | InfoStyle::InlineRight) {
+            segments.push((" ".to_string(), info_attr));
+        }
+
+        let (spinner, spinner_attr) = self.spinner();
+        segments.push((spinner.to_string(), spinner_attr));
+
+        if let Some(format) = self.info_format.as_ref() {
+            segments.push((format!(" {}", self.expand_format(format)), info_attr));
+        } else {
+            // display matched/total number
+            segments.push((format!(" {}/{}", self.matched, self.total), info_attr));
+
+            // display the matcher mode
+            if !self.matcher_mode.is_empty() {
+                segments.push((format!("/{}", &self.matcher_mode), info_attr));
+            }
+
+            // display the percentage of the number of processed items
+            if self.matcher_running && self.time_since_match > Duration::from_millis(50) {
+                segments.push((format!(" ({}%) ", self.percentage()), info_attr));
+            }
+
+            // selected number
+            if self.multi_selection && self.selected > 0 {
+                segments.push((format!(" [{}]", self.selected), info_attr_bold));
+            }
+        }
+
+        if self.info == InfoStyle::RightOfPrompt {
+            segments.push((" ".to_string(), info_attr));
+        }
+
+        segments
+    }
+
+    fn width(&self) -> usize {
+        self.segments().iter().map(|(text, _)| text.width_cjk()).sum()
+    }
 }
 
-#[allow(unused_assignments)]
 impl Draw for Status {
     fn draw(&self, canvas: &mut dyn Canvas) -> DrawResult<()> {
         // example:
@@ -979,75 +1311,52 @@ impl Draw for Status {
         let (screen_width, _) = canvas.size()?;
         clear_canvas(canvas)?;
 
-        let info_attr = self.theme.info();
-        let info_attr_bold = Attr {
-            effect: Effect::BOLD,
-            ..self.theme.info()
-        };
-
-        let a_while_since_read = self.time_since_read > Duration::from_millis(50);
-        let a_while_since_match = self.time_since_match > Duration::from_millis(50);
-
-        let mut col = 0;
-        let spinner_set: &[char] = if self.inline_info {
-            &SPINNERS_INLINE
+        let mut col = if self.info == InfoStyle::InlineRight {
+            screen_width.saturating_sub(self.width())
         } else {
-            &SPINNERS_UNICODE
+            0
         };
 
-        if self.inline_info {
-            col += canvas.put_char_with_attr(0, col, ' ', info_attr)?;
+        for (text, attr) in self.segments() {
+            col += canvas.print_with_attr(0, col, &text, attr)?;
         }
 
-        // draw the spinner
-        if self.reading && a_while_since_read {
-            let mills = (self.time_since_read.as_secs() * 1000) as u32 + self.time_since_read.subsec_millis();
-            let index = (mills / SPINNER_DURATION) % (spinner_set.len() as u32);
-            let ch = spinner_set[index as usize];
-            col += canvas.put_char_with_attr(0, col, ch, self.theme.spinner())?;
-        } else if self.inline_info {
-            col += canvas.put_char_with_attr(0, col, '<', self.theme.prompt())?;
-        } else {
-            col += canvas.put_char_with_attr(0, col, ' ', self.theme.prompt())?;
-        }
-
-        // display matched/total number
-        col += canvas.print_with_attr(0, col, format!(" {}/{}", self.matched, self.total).as_ref(), info_attr)?;
-
-        // display the matcher mode
-        if !self.matcher_mode.is_empty() {
-            col += canvas.print_with_attr(0, col, format!("/{}", &self.matcher_mode).as_ref(), info_attr)?;
-        }
-
-        // display the percentage of the number of processed items
-        if self.matcher_running && a_while_since_match {
-            col += canvas.print_with_attr(
+        // item cursor, only in the full size styles without a custom format
+        let show_item_cursor =
+            self.info_format.is_none() && matches!(self.info, InfoStyle::Default | InfoStyle::Inline);
+        if show_item_cursor {
+            let info_attr_bold = Attr {
+                effect: Effect::BOLD,
+                ..self.theme.info()
+            };
+            let line_num_str = format!(
+                " {}/{}{}",
+                self.current_item_idx,
+                self.hscroll_offset,
+                if self.matcher_running { '.' } else { ' ' }
+            );
+            canvas.print_with_attr(
                 0,
-                col,
-                format!(" ({}%) ", self.processed * 100 / self.total).as_ref(),
-                info_attr,
+                screen_width.saturating_sub(line_num_str.len()),
+                &line_num_str,
+                info_attr_bold,
             )?;
         }
 
-        // selected number
-        if self.multi_selection && self.selected > 0 {
-            col += canvas.print_with_attr(0, col, format!(" [{}]", self.selected).as_ref(), info_attr_bold)?;
-        }
-
-        // item cursor
-        let line_num_str = format!(
-            " {}/{}{}",
-            self.current_item_idx,
-            self.hscroll_offset,
-            if self.matcher_running { '.' } else { ' ' }
-        );
-        canvas.print_with_attr(0, screen_width - line_num_str.len(), &line_num_str, info_attr_bold)?;
-
         Ok(())
     }
 }
 
-impl Widget<Event> for Status {}
+impl Widget<Event> for Status {
+    fn size_hint(&self) -> (Option<usize>, Option<usize>) {
+        // only sized when squeezed between the prompt and the query
+        if self.info == InfoStyle::RightOfPrompt {
+            (Some(self.width()), None)
+        } else {
+            (None, None)
+        }
+    }
+}
 
 #[derive(PartialEq, Eq, Clone, Debug, Copy)]
 enum Direction {
@@ -1057,9 +1366,102 @@ enum Direction {
     Right,
 }
 
+/// Where (and whether) the info line is displayed
+#[derive(PartialEq, Eq, Clone, Debug, Copy)]
+pub enum InfoStyle {
+    /// on its own line
+    Default,
+    /// next to the query
+    Inline,
+    /// on the query line, aligned to the right
+    InlineRight,
+    /// between the prompt and the query
+    RightOfPrompt,
+    Hidden,
+}
+
+impl InfoStyle {
+    pub fn parse(style: &str) -> std::result::Result<Self, String> {
+        let style = match style {
+            "default" => InfoStyle::Default,
+            "inline" => InfoStyle::Inline,
+            "inline-right" => InfoStyle::InlineRight,
+            "right-of-prompt" => InfoStyle::RightOfPrompt,
+            "hidden" => InfoStyle::Hidden,
+            _ => return Err(format!("unknown info style '{}'", style)),
+        };
+        Ok(style)
+    }
+
+    /// whether the info shares the line with the query
+    fn is_inline(self) -> bool {
+        matches!(
+            self,
+            InfoStyle::Inline | InfoStyle::InlineRight | InfoStyle::RightOfPrompt
+        )
+    }
+}
+
 #[derive(PartialEq, Eq, Clone, Debug, Copy)]
 enum ClearStrategy {
     DontClear,
     Clear,
     ClearIfNotNull,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::theme::DEFAULT_THEME;
+
+    #[test]
+    fn test_info_format() {
+        let status = Status {
+            total: 100,
+            matched: 42,
+            processed: 50,
+            matcher_running: true,
+            multi_selection: true,
+            selected: 3,
+            current_item_idx: 7,
+            hscroll_offset: 2,
+            reading: false,
+            time_since_read: Duration::from_secs(1),
+            time_since_match: Duration::from_secs(1),
+            matcher_mode: "RE".to_string(),
+            theme: Arc::new(*DEFAULT_THEME),
+            info: InfoStyle::parse("inline-right").unwrap(),
+            info_format: None,
+        };
+
+        assert_eq!(
+            status.expand_format("{matched}/{total} [{selected}] {mode} {percent}% {current}/{hscroll}"),
+            "42/100 [3] RE 50% 7/2"
+        );
+        assert_eq!(status.info, InfoStyle::InlineRight);
+        assert!(status.info.is_inline());
+        assert!(!InfoStyle::parse("default").unwrap().is_inline());
+        assert!(InfoStyle::parse("unknown").is_err());
+    }
+
+    #[test]
+    fn test_preview_layout() {
+        let (direction, size, wrap, shown, follow) = Model::parse_preview_layout("right:hidden:wrap", "down,40%");
+        assert_eq!(direction, Direction::Down);
+        assert!(matches!(size, Size::Percent(40)));
+        assert!(wrap);
+        assert!(shown);
+        assert!(!follow);
+
+        let (direction, size, wrap, shown, follow) = Model::parse_preview_layout("up:10:follow", "nowrap:hidden");
+        assert_eq!(direction, Direction::Up);
+        assert!(matches!(size, Size::Fixed(10)));
+        assert!(!wrap);
+        assert!(!shown);
+        assert!(follow);
+
+        assert!(matches!(Model::resize_preview(Size::Percent(50), 5), Size::Percent(55)));
+        assert!(matches!(Model::resize_preview(Size::Percent(3), -5), Size::Percent(1)));
+        assert!(matches!(Model::resize_preview(Size::Fixed(30), -10), Size::Fixed(20)));
+    }
+}
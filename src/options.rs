@@ -2,8 +2,11 @@ use std::rc::Rc;
 
 use derive_builder::Builder;
 
+use crate::group::GroupOrder;
 use crate::helper::item_reader::SkimItemReader;
+use crate::model::InfoStyle;
 use crate::reader::CommandCollector;
+use crate::util::parse_duration;
 use crate::{CaseMatching, FuzzyAlgorithm, MatchEngineFactory, Selector};
 use std::cell::RefCell;
 use std::sync::Arc;
@@ -16,9 +19,12 @@ pub struct SkimOptions<'a> {
     pub multi: bool,
     pub prompt: Option<&'a str>,
     pub cmd_prompt: Option<&'a str>,
+    pub ghost: Option<&'a str>,
+    pub cmd_ghost: Option<&'a str>,
     pub expect: Option<String>,
     pub tac: bool,
     pub nosort: bool,
+    pub top_k: Option<&'a str>,
     pub tiebreak: Option<String>,
     pub exact: bool,
     pub disabled: bool,
@@ -30,6 +36,7 @@ pub struct SkimOptions<'a> {
     pub delimiter: Option<&'a str>,
     pub replstr: Option<&'a str>,
     pub color: Option<&'a str>,
+    pub no_bold: bool,
     pub margin: Option<&'a str>,
     pub no_height: bool,
     pub no_clear: bool,
@@ -38,13 +45,40 @@ pub struct SkimOptions<'a> {
     pub height: Option<&'a str>,
     pub preview: Option<&'a str>,
     pub preview_window: Option<&'a str>,
+    pub preview_interval: Option<&'a str>,
+    pub preview_cache_size: usize,
     pub reverse: bool,
     pub tabstop: Option<&'a str>,
     pub no_hscroll: bool,
+    pub scrollbar: Option<&'a str>,
+    pub multi_line: Option<&'a str>,
+    pub tabular: Option<&'a str>,
+    pub tree: Option<&'a str>,
+    pub groups: Option<&'a str>,
+    pub hscroll_off: Option<&'a str>,
     pub no_mouse: bool,
+    pub jump_labels: Option<&'a str>,
+    pub cycle: bool,
     pub inline_info: bool,
+    pub info: Option<&'a str>,
+    pub info_format: Option<&'a str>,
     pub header: Option<&'a str>,
     pub header_lines: usize,
+    pub pointer: Option<&'a str>,
+    pub marker: Option<&'a str>,
+    pub unmarked: Option<&'a str>,
+    pub border: Option<&'a str>,
+    pub border_label: Option<&'a str>,
+    pub border_label_pos: Option<&'a str>,
+    pub list_border: Option<&'a str>,
+    pub list_label: Option<&'a str>,
+    pub list_label_pos: Option<&'a str>,
+    pub header_border: Option<&'a str>,
+    pub header_label: Option<&'a str>,
+    pub header_label_pos: Option<&'a str>,
+    pub preview_border: Option<&'a str>,
+    pub preview_label: Option<&'a str>,
+    pub preview_label_pos: Option<&'a str>,
     pub layout: &'a str,
     pub algorithm: FuzzyAlgorithm,
     pub case: CaseMatching,
@@ -68,9 +102,12 @@ impl<'a> Default for SkimOptions<'a> {
             multi: false,
             prompt: Some("> "),
             cmd_prompt: Some("c> "),
+            ghost: None,
+            cmd_ghost: None,
             expect: None,
             tac: false,
             nosort: false,
+            top_k: None,
             tiebreak: None,
             exact: false,
             disabled: false,
@@ -82,6 +119,7 @@ impl<'a> Default for SkimOptions<'a> {
             delimiter: None,
             replstr: Some("{}"),
             color: None,
+            no_bold: false,
             margin: Some("0,0,0,0"),
             no_height: false,
             no_clear: false,
@@ -90,13 +128,40 @@ impl<'a> Default for SkimOptions<'a> {
             height: Some("100%"),
             preview: None,
             preview_window: Some("right:50%"),
+            preview_interval: None,
+            preview_cache_size: 0,
             reverse: false,
             tabstop: None,
             no_hscroll: false,
+            scrollbar: None,
+            multi_line: None,
+            tabular: None,
+            tree: None,
+            groups: None,
+            hscroll_off: None,
             no_mouse: false,
+            jump_labels: None,
+            cycle: false,
             inline_info: false,
+            info: None,
+            info_format: None,
             header: None,
             header_lines: 0,
+            pointer: None,
+            marker: None,
+            unmarked: None,
+            border: None,
+            border_label: None,
+            border_label_pos: None,
+            list_border: None,
+            list_label: None,
+            list_label_pos: None,
+            header_border: None,
+            header_label: None,
+            header_label_pos: None,
+            preview_border: None,
+            preview_label: None,
+            preview_label_pos: None,
             layout: "",
             algorithm: FuzzyAlgorithm::default(),
             case: CaseMatching::default(),
@@ -115,6 +180,36 @@ impl<'a> Default for SkimOptions<'a> {
     }
 }
 
+impl<'a> SkimOptions<'a> {
+    /// check the values that can't be used, e.g. `top_k` is neither a positive number nor `auto`
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(top_k) = self.top_k {
+            if top_k != "auto" && !matches!(top_k.parse::<usize>(), Ok(k) if k > 0) {
+                return Err(format!("invalid --top-k: {}", top_k));
+            }
+        }
+
+        if let Some(order) = self.groups {
+            if self.tree.is_some() {
+                return Err("--tree and groups can't be used together".to_string());
+            }
+            GroupOrder::parse(order).map_err(|err| format!("invalid groups: {}", err))?;
+        }
+
+        if let Some(info) = self.info {
+            InfoStyle::parse(info).map_err(|err| format!("invalid --info: {}", err))?;
+        }
+
+        if let Some(interval) = self.preview_interval {
+            if parse_duration(interval).is_none() {
+                return Err(format!("invalid --preview-interval: {}", interval));
+            }
+        }
+
+        Ok(())
+    }
+}
+
 impl<'a> Drop for SkimOptionsBuilder<'a> {
     fn drop(&mut self) {
         self.cmd_collector.take();
@@ -135,3 +230,46 @@ impl<'a> SkimOptionsBuilder<'a> {
         self.final_build()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        assert!(SkimOptions::default().validate().is_ok());
+
+        let options = |top_k| SkimOptions {
+            top_k: Some(top_k),
+            ..Default::default()
+        };
+        assert!(options("auto").validate().is_ok());
+        assert!(options("100").validate().is_ok());
+        assert!(options("0").validate().is_err());
+        assert!(options("abc").validate().is_err());
+
+        let options = |groups, tree| SkimOptions {
+            groups: Some(groups),
+            tree,
+            ..Default::default()
+        };
+        assert!(options("index", None).validate().is_ok());
+        assert!(options("name", None).validate().is_err());
+        assert!(options("rank", Some("/")).validate().is_err());
+
+        let options = |interval| SkimOptions {
+            preview_interval: Some(interval),
+            ..Default::default()
+        };
+        assert!(options("500ms").validate().is_ok());
+        assert!(options("0").validate().is_err());
+        assert!(options("1m").validate().is_err());
+
+        let options = |info| SkimOptions {
+            info: Some(info),
+            ..Default::default()
+        };
+        assert!(options("inline-right").validate().is_ok());
+        assert!(options("inline-left").validate().is_err());
+    }
+}
@@ -1,9 +1,13 @@
 // ordered container
 // Normally, user will only care about the first several options. So we only keep several of them
 // in order. Other items are kept unordered and are sorted on demand.
+//
+// With `top_k`, only the best K items are tracked (in a heap) while appending, so that huge
+// results need not be sorted at all. Deeper pages are selected and sorted when requested.
 
 use std::cell::RefCell;
-use std::cmp::Ordering;
+use std::cmp::{max, min, Ordering};
+use std::collections::BinaryHeap;
 
 const ORDERED_SIZE: usize = 300;
 const MAX_MOVEMENT: usize = 100;
@@ -13,8 +17,13 @@ pub struct OrderedVec<T: Send + Ord + Clone> {
     sub_vectors: RefCell<Vec<Vec<T>>>,
     // globally sorted items, the first one is the smallest one.
     sorted: RefCell<Vec<T>>,
+    // best `top_k` items that are not in `sorted` yet, the top one is the worst of them
+    heap: RefCell<BinaryHeap<HeapEntry<T>>>,
+    // items that are worse than the ones in `heap`, unordered
+    rest: RefCell<Vec<T>>,
     tac: bool,
     nosort: bool,
+    top_k: Option<usize>,
 }
 
 impl<T: Send + Ord + Clone> Drop for OrderedVec<T> {
@@ -22,9 +31,13 @@ impl<T: Send + Ord + Clone> Drop for OrderedVec<T> {
         // guarantees not borrowed elsewhere
         let sub_vectors = std::mem::take(self.sub_vectors.get_mut());
         let sorted = std::mem::take(self.sorted.get_mut());
+        let heap = std::mem::take(self.heap.get_mut());
+        let rest = std::mem::take(self.rest.get_mut());
 
         drop(sub_vectors);
         drop(sorted);
+        drop(heap);
+        drop(rest);
     }
 }
 
@@ -39,8 +52,11 @@ impl<T: Send + Ord + Clone> OrderedVec<T> {
         OrderedVec {
             sub_vectors: RefCell::new(Vec::new()),
             sorted: RefCell::new(Vec::with_capacity(ORDERED_SIZE)),
+            heap: RefCell::new(BinaryHeap::new()),
+            rest: RefCell::new(Vec::new()),
             tac: false,
             nosort: false,
+            top_k: None,
         }
     }
 
@@ -54,6 +70,12 @@ impl<T: Send + Ord + Clone> OrderedVec<T> {
         self
     }
 
+    /// only keep the best `k` items ordered, `None` to order all of them
+    pub fn top_k(&mut self, k: Option<usize>) -> &mut Self {
+        self.top_k = k.map(|k| max(k, 1));
+        self
+    }
+
     pub fn append(&mut self, mut items: Vec<T>) {
         trace!("orderedvec append: new vec size: {}", items.len());
         if self.nosort {
@@ -61,6 +83,11 @@ impl<T: Send + Ord + Clone> OrderedVec<T> {
             return;
         }
 
+        if let Some(k) = self.top_k {
+            self.append_top_k(items, k);
+            return;
+        }
+
         self.sort_vector(&mut items, false);
         let mut items_smaller = Vec::new();
 
@@ -104,6 +131,49 @@ impl<T: Send + Ord + Clone> OrderedVec<T> {
         );
     }
 
+    // `sorted` and `heap` hold the best (at most) `k` items, the sorted ones first. A new item only
+    // competes with the worst of them, the others go to `rest` without being ordered.
+    fn append_top_k(&mut self, items: Vec<T>, k: usize) {
+        let tac = self.tac;
+        let is_better = |a: &T, b: &T| if tac { b < a } else { a < b };
+        let sorted = self.sorted.get_mut();
+        let heap = self.heap.get_mut();
+        let rest = self.rest.get_mut();
+
+        // the items sorted beyond the best `k` by scrolling are selected again when requested
+        if sorted.len() > k {
+            rest.extend(sorted.drain(k..));
+        }
+
+        for item in items {
+            let worst = heap.peek().map(|entry| &entry.item).or_else(|| sorted.last());
+            if sorted.len() + heap.len() >= k && worst.map(|worst| !is_better(&item, worst)) == Some(true) {
+                rest.push(item);
+                continue;
+            }
+
+            // better than a sorted item, let the (at most `k`) sorted ones compete again
+            if sorted.last().map(|last| is_better(&item, last)) == Some(true) {
+                heap.extend(sorted.drain(..).map(|item| HeapEntry { item, tac }));
+            }
+            heap.push(HeapEntry { item, tac });
+
+            if sorted.len() + heap.len() > k {
+                let worst = match heap.pop() {
+                    Some(entry) => entry.item,
+                    None => sorted.pop().unwrap(),
+                };
+                rest.push(worst);
+            }
+        }
+
+        trace!(
+            "orderedvec done append: top_k: {}, rest size: {}",
+            heap.len(),
+            rest.len()
+        );
+    }
+
     fn sort_vector(&self, vec: &mut [T], asc: bool) {
         let asc = asc ^ self.tac;
         vec.sort();
@@ -123,6 +193,11 @@ impl<T: Send + Ord + Clone> OrderedVec<T> {
     }
 
     fn merge_till(&self, index: usize) {
+        if let (Some(k), false) = (self.top_k, self.nosort) {
+            self.select_till(index, k);
+            return;
+        }
+
         let mut sorted = self.sorted.borrow_mut();
         let mut vectors = self.sub_vectors.borrow_mut();
 
@@ -156,6 +231,43 @@ impl<T: Send + Ord + Clone> OrderedVec<T> {
         }
     }
 
+    // move items from `heap` and then `rest` into `sorted` until `index` is covered.
+    // at least `k` items are selected each time, so that scrolling does not select on every line.
+    fn select_till(&self, index: usize, k: usize) {
+        let mut sorted = self.sorted.borrow_mut();
+        let mut heap = self.heap.borrow_mut();
+        let mut rest = self.rest.borrow_mut();
+
+        while index >= sorted.len() {
+            if !heap.is_empty() {
+                // ascending order of `HeapEntry` means best first
+                let top = std::mem::take(&mut *heap).into_sorted_vec();
+                sorted.extend(top.into_iter().map(|entry| entry.item));
+                continue;
+            }
+
+            if rest.is_empty() {
+                break;
+            }
+
+            let num = max(k, index + 1 - sorted.len());
+            trace!(
+                "select_till: index: {}, num_sorted: {}, num_select: {}",
+                index,
+                sorted.len(),
+                num
+            );
+            if rest.len() > num {
+                rest.select_nth_unstable_by(num, |a, b| self.compare_item(a, b));
+            }
+            let split_at = min(num, rest.len());
+            let remaining = rest.split_off(split_at);
+            let mut selected = std::mem::replace(&mut *rest, remaining);
+            selected.sort_by(|a, b| self.compare_item(a, b));
+            sorted.append(&mut selected);
+        }
+    }
+
     pub fn get(&self, index: usize) -> Option<T> {
         self.merge_till(index);
         if self.len() <= index {
@@ -173,12 +285,14 @@ impl<T: Send + Ord + Clone> OrderedVec<T> {
     pub fn len(&self) -> usize {
         let sorted_len = self.sorted.borrow().len();
         let unsorted_len: usize = self.sub_vectors.borrow().iter().map(|v| v.len()).sum();
-        sorted_len + unsorted_len
+        sorted_len + unsorted_len + self.heap.borrow().len() + self.rest.borrow().len()
     }
 
     pub fn clear(&mut self) {
         self.sub_vectors.get_mut().clear();
         self.sorted.get_mut().clear();
+        self.heap.get_mut().clear();
+        self.rest.get_mut().clear();
     }
 
     pub fn is_empty(&self) -> bool {
@@ -209,6 +323,36 @@ impl<'a, T: Send + Ord + Clone> Iterator for OrderedVecIter<'a, T> {
     }
 }
 
+// heap entry ordered the way items are shown, so the top of a `BinaryHeap` is the worst one
+struct HeapEntry<T> {
+    item: T,
+    tac: bool,
+}
+
+impl<T: Ord> PartialEq for HeapEntry<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T: Ord> Eq for HeapEntry<T> {}
+
+impl<T: Ord> PartialOrd for HeapEntry<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Ord> Ord for HeapEntry<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        if !self.tac {
+            self.item.cmp(&other.item)
+        } else {
+            other.item.cmp(&self.item)
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -300,4 +444,70 @@ mod tests {
             assert_eq!(&a, b);
         }
     }
+
+    #[test]
+    fn test_top_k() {
+        let a = vec![7, 3, 5, 1];
+        let b = vec![9, 4, 8];
+        let c = vec![2, 10, 6];
+        let mut ordered_vec = OrderedVec::new();
+        ordered_vec.top_k(Some(3));
+
+        ordered_vec.append(a);
+        assert_eq!(ordered_vec.len(), 4);
+        assert_eq!(ordered_vec.get(0).unwrap(), 1);
+
+        ordered_vec.append(b);
+        assert_eq!(ordered_vec.get(1).unwrap(), 3);
+        assert_eq!(ordered_vec.get(5).unwrap(), 8);
+
+        ordered_vec.append(c);
+        assert_eq!(ordered_vec.len(), 10);
+        assert_eq!(ordered_vec.get(0).unwrap(), 1);
+        assert_eq!(ordered_vec.get(1).unwrap(), 2);
+        for (idx, item) in ordered_vec.iter().enumerate() {
+            assert_eq!(idx + 1, item)
+        }
+        assert_eq!(ordered_vec.get(10), None);
+    }
+
+    #[test]
+    fn test_top_k_after_scrolling() {
+        let mut ordered_vec = OrderedVec::new();
+        ordered_vec.top_k(Some(2));
+        ordered_vec.append(vec![9, 3, 7, 5]);
+        // scroll to the end, all the items are sorted
+        assert_eq!(ordered_vec.get(3).unwrap(), 9);
+
+        // only the best `k` stay sorted, the new items compete with them alone
+        ordered_vec.append(vec![8, 4, 1]);
+        assert!(ordered_vec.sorted.borrow().len() + ordered_vec.heap.borrow().len() <= 2);
+        assert_eq!(ordered_vec.iter().collect::<Vec<_>>(), vec![1, 3, 4, 5, 7, 8, 9]);
+
+        ordered_vec.append(vec![6, 2, 10]);
+        assert_eq!(
+            ordered_vec.iter().collect::<Vec<_>>(),
+            vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10]
+        );
+    }
+
+    #[test]
+    fn test_top_k_tac() {
+        let a = vec![7, 3, 5, 1];
+        let b = vec![9, 4, 8];
+        let c = vec![2, 10, 6];
+        let mut ordered_vec = OrderedVec::new();
+        ordered_vec.top_k(Some(2)).tac(true);
+
+        ordered_vec.append(a);
+        assert_eq!(ordered_vec.get(0).unwrap(), 7);
+
+        ordered_vec.append(b);
+        assert_eq!(ordered_vec.get(3).unwrap(), 5);
+
+        ordered_vec.append(c);
+        for (idx, item) in ordered_vec.iter().enumerate() {
+            assert_eq!(10 - idx, item)
+        }
+    }
 }
@@ -1,11 +1,15 @@
-use crossbeam_channel::{unbounded, Receiver, Sender};
+use crossbeam_channel::{unbounded, Receiver, RecvTimeoutError, Sender};
 use std::cmp::{max, min};
+use std::collections::VecDeque;
+use std::io::Read;
+use std::os::unix::process::CommandExt;
 use std::path::PathBuf;
-use std::process::{Command, Stdio};
+use std::process::{Child, Command, Stdio};
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::thread;
 use std::thread::JoinHandle;
+use std::time::Duration;
 
 use derive_builder::Builder;
 use regex::Regex;
@@ -14,6 +18,7 @@ use tuikit::prelude::{Event as TermEvent, *};
 use crate::ansi::{ANSIParser, AnsiString};
 use crate::event::{Event, EventHandler, UpdateScreen};
 use crate::item::MatchedItem;
+use crate::scrollbar::Scrollbar;
 use crate::spinlock::SpinLock;
 use crate::util::{atoi, clear_canvas, depends_on_items, inject_command, InjectContext};
 use crate::{ItemPreview, PreviewContext, PreviewPosition, SkimItem};
@@ -35,6 +40,13 @@ pub struct Previewer {
     hscroll_offset: Arc<AtomicUsize>,
     vscroll_offset: Arc<AtomicUsize>,
     wrap: bool,
+    // stream the output of the command and keep the view at the bottom
+    follow: bool,
+    // re-run the command periodically while the item stays the same
+    interval: Option<Duration>,
+    cache: Arc<SpinLock<PreviewCache>>,
+    // the glyph and the attribute of the scrollbar, `None` for no scrollbar
+    scrollbar: Option<(char, Attr)>,
 
     prev_item: Option<Arc<dyn SkimItem>>,
     prev_query: Option<String>,
@@ -55,27 +67,39 @@ impl Previewer {
         let height = Arc::new(AtomicUsize::new(60));
         let hscroll_offset = Arc::new(AtomicUsize::new(1));
         let vscroll_offset = Arc::new(AtomicUsize::new(1));
+        let cache = Arc::new(SpinLock::new(PreviewCache::default()));
 
         let content_clone = content_lines.clone();
         let width_clone = width.clone();
         let height_clone = height.clone();
         let hscroll_offset_clone = hscroll_offset.clone();
         let vscroll_offset_clone = vscroll_offset.clone();
+        let cache_clone = cache.clone();
         let thread_previewer = thread::spawn(move || {
             run(
                 rx_preview,
-                Box::new(move |lines, pos| {
+                cache_clone,
+                Box::new(move |lines, pos: Option<PreviewPosition>| {
                     let width = width_clone.load(Ordering::SeqCst);
                     let height = height_clone.load(Ordering::SeqCst);
 
-                    let hscroll = pos.h_scroll.calc_fixed_size(lines.len(), 0);
-                    let hoffset = pos.h_offset.calc_fixed_size(width, 0);
-                    let vscroll = pos.v_scroll.calc_fixed_size(usize::MAX, 0);
-                    let voffset = pos.v_offset.calc_fixed_size(height, 0);
+                    let mut content = content_clone.lock();
+                    match lines {
+                        PreviewLines::All(lines) => *content = lines,
+                        PreviewLines::More(lines) => content.extend(lines),
+                    }
+
+                    // keep the current offsets if no position is given
+                    if let Some(pos) = pos {
+                        let hscroll = pos.h_scroll.calc_fixed_size(content.len(), 0);
+                        let hoffset = pos.h_offset.calc_fixed_size(width, 0);
+                        let vscroll = pos.v_scroll.calc_fixed_size(usize::MAX, 0);
+                        let voffset = pos.v_offset.calc_fixed_size(height, 0);
 
-                    hscroll_offset_clone.store(max(1, max(hscroll, hoffset) - hoffset), Ordering::SeqCst);
-                    vscroll_offset_clone.store(max(1, max(vscroll, voffset) - voffset), Ordering::SeqCst);
-                    *content_clone.lock() = lines;
+                        hscroll_offset_clone.store(max(1, max(hscroll, hoffset) - hoffset), Ordering::SeqCst);
+                        vscroll_offset_clone.store(max(1, max(vscroll, voffset) - voffset), Ordering::SeqCst);
+                    }
+                    drop(content);
 
                     callback();
                 }),
@@ -91,6 +115,10 @@ impl Previewer {
             hscroll_offset,
             vscroll_offset,
             wrap: false,
+            follow: false,
+            interval: None,
+            cache,
+            scrollbar: None,
 
             prev_item: None,
             prev_query: None,
@@ -109,6 +137,39 @@ impl Previewer {
         self
     }
 
+    pub fn set_wrap(&mut self, wrap: bool) {
+        self.wrap = wrap;
+    }
+
+    pub fn follow(mut self, follow: bool) -> Self {
+        self.follow = follow;
+        self
+    }
+
+    pub fn set_follow(&mut self, follow: bool) {
+        self.follow = follow;
+    }
+
+    pub fn interval(mut self, interval: Option<Duration>) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    // the number of outputs to keep, 0 to disable the cache
+    pub fn cache_size(self, size: usize) -> Self {
+        self.cache.lock().capacity = size;
+        self
+    }
+
+    pub fn clear_cache(&self) {
+        self.cache.lock().clear();
+    }
+
+    pub fn scrollbar(mut self, scrollbar: Option<(char, Attr)>) -> Self {
+        self.scrollbar = scrollbar;
+        self
+    }
+
     pub fn delimiter(mut self, delimiter: Regex) -> Self {
         self.delimiter = delimiter;
         self
@@ -222,7 +283,13 @@ impl Previewer {
                         PreviewEvent::PreviewPlainText("no item matched".to_string(), Default::default())
                     } else {
                         let cmd = inject_command(&cmd, inject_context).to_string();
-                        let preview_command = PreviewCommand { cmd, columns, lines };
+                        let preview_command = PreviewCommand {
+                            cmd,
+                            columns,
+                            lines,
+                            follow: self.follow,
+                            interval: self.interval,
+                        };
                         PreviewEvent::PreviewCommand(preview_command, pos)
                     }
                 }
@@ -235,7 +302,13 @@ impl Previewer {
                     } else {
                         let cmd = inject_command(&cmd, inject_context).to_string();
                         let pos = self.eval_scroll_offset(inject_context);
-                        let preview_command = PreviewCommand { cmd, columns, lines };
+                        let preview_command = PreviewCommand {
+                            cmd,
+                            columns,
+                            lines,
+                            follow: self.follow,
+                            interval: self.interval,
+                        };
                         PreviewEvent::PreviewCommand(preview_command, pos)
                     }
                 }
@@ -268,6 +341,18 @@ impl Previewer {
         self.hscroll_offset.store(max(1, new_offset), Ordering::SeqCst);
     }
 
+    // `line` is counted from 0
+    fn act_scroll_to(&mut self, line: usize) {
+        self.vscroll_offset.store(line + 1, Ordering::SeqCst);
+    }
+
+    // `None` if disabled or the whole content is visible
+    fn get_scrollbar(&self, total: usize, height: usize) -> Option<Scrollbar> {
+        self.scrollbar?;
+        let offset = self.vscroll_offset.load(Ordering::SeqCst);
+        Scrollbar::new(total, height, max(1, offset) - 1, height)
+    }
+
     fn act_toggle_wrap(&mut self) {
         self.wrap = !self.wrap;
     }
@@ -352,6 +437,7 @@ impl EventHandler for Previewer {
             EvActPreviewRight(diff) => self.act_scroll_right(*diff),
             EvActPreviewPageUp(diff) => self.act_scroll_down(-(height as i32 * *diff)),
             EvActPreviewPageDown(diff) => self.act_scroll_down(height as i32 * *diff),
+            EvActPreviewScrollTo(line) => self.act_scroll_to(*line),
             _ => return UpdateScreen::DONT_REDRAW,
         }
         UpdateScreen::REDRAW
@@ -376,6 +462,19 @@ impl Draw for Previewer {
         let vscroll_offset = self.vscroll_offset.load(Ordering::SeqCst);
         let hscroll_offset = self.hscroll_offset.load(Ordering::SeqCst);
 
+        // the scrollbar takes the last column
+        let scrollbar = self
+            .get_scrollbar(content.len(), screen_height)
+            .filter(|_| screen_width > 1);
+        let screen_width = if scrollbar.is_some() {
+            screen_width - 1
+        } else {
+            screen_width
+        };
+        if let (Some(scrollbar), Some((glyph, attr))) = (scrollbar, self.scrollbar) {
+            scrollbar.draw(canvas, screen_width, glyph, attr, false);
+        }
+
         let mut printer = PrinterBuilder::default()
             .width(screen_width)
             .height(screen_height)
@@ -404,9 +503,18 @@ impl Draw for Previewer {
 }
 
 impl Widget<Event> for Previewer {
-    fn on_event(&self, event: TermEvent, _rect: Rectangle) -> Vec<Event> {
+    fn on_event(&self, event: TermEvent, rect: Rectangle) -> Vec<Event> {
         let mut ret = vec![];
+        let scrollbar = self.get_scrollbar(self.content_lines.lock().len(), rect.height);
         match event {
+            TermEvent::Key(Key::SingleClick(MouseButton::Left, row, col))
+                if scrollbar.is_some() && col as usize + 1 == rect.width =>
+            {
+                let line = scrollbar
+                    .map(|scrollbar| scrollbar.offset_at(row as usize))
+                    .unwrap_or(0);
+                ret.push(Event::EvActPreviewScrollTo(line))
+            }
             TermEvent::Key(Key::WheelUp(.., count)) => ret.push(Event::EvActPreviewUp(count as i32)),
             TermEvent::Key(Key::WheelDown(.., count)) => ret.push(Event::EvActPreviewDown(count as i32)),
             _ => {}
@@ -415,11 +523,60 @@ impl Widget<Event> for Previewer {
     }
 }
 
-#[derive(Debug, Ord, PartialOrd, PartialEq, Eq)]
+#[derive(Debug, Clone, Ord, PartialOrd, PartialEq, Eq)]
 pub struct PreviewCommand {
     pub cmd: String,
     pub lines: usize,
     pub columns: usize,
+    pub follow: bool,
+    pub interval: Option<Duration>,
+}
+
+impl PreviewCommand {
+    // the output of the commands which keeps changing is not cached
+    fn cache_key(&self) -> Option<PreviewKey> {
+        if self.follow || self.interval.is_some() {
+            None
+        } else {
+            Some((self.cmd.clone(), self.columns, self.lines))
+        }
+    }
+}
+
+// (command, columns, lines)
+type PreviewKey = (String, usize, usize);
+
+// LRU cache of the outputs of the preview commands
+#[derive(Default)]
+struct PreviewCache {
+    capacity: usize,
+    // the most recently used one at the back
+    entries: VecDeque<(PreviewKey, Vec<AnsiString>)>,
+}
+
+impl PreviewCache {
+    fn get(&mut self, key: &PreviewKey) -> Option<Vec<AnsiString>> {
+        let index = self.entries.iter().position(|(k, _)| k == key)?;
+        let entry = self.entries.remove(index)?;
+        let lines = entry.1.clone();
+        self.entries.push_back(entry);
+        Some(lines)
+    }
+
+    fn insert(&mut self, key: PreviewKey, lines: &[AnsiString]) {
+        if self.capacity == 0 {
+            return;
+        }
+        self.entries.retain(|(k, _)| *k != key);
+        while self.entries.len() >= self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back((key, lines.to_vec()));
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+    }
 }
 
 #[derive(Debug)]
@@ -427,6 +584,8 @@ enum PreviewEvent {
     PreviewCommand(PreviewCommand, PreviewPosition),
     PreviewPlainText(String, PreviewPosition),
     PreviewAnsiText(String, PreviewPosition),
+    // re-run the last command, keeping the scroll offsets
+    Refresh,
     Noop,
     Abort,
 }
@@ -445,8 +604,9 @@ impl Drop for PreviewThread {
 
 impl PreviewThread {
     fn kill(&mut self) {
+        // kill the whole process group, the commands of a pipeline might keep the output open
         if !self.stopped.load(Ordering::Relaxed) {
-            unsafe { libc::kill(self.pid as i32, libc::SIGKILL) };
+            unsafe { libc::kill(-(self.pid as i32), libc::SIGKILL) };
         }
         if let Some(handle) = self.thread.take() {
             let _ = handle.join();
@@ -459,100 +619,254 @@ impl PreviewThread {
     }
 }
 
-fn run(rx_preview: Receiver<PreviewEvent>, on_return: Box<dyn Fn(Vec<AnsiString>, PreviewPosition) + Send + Sync>) {
+// the output of the preview
+enum PreviewLines {
+    // replace the content
+    All(Vec<AnsiString>),
+    // append to the content, i.e. the output streamed in the follow mode
+    More(Vec<AnsiString>),
+}
+
+type PreviewCallback = Arc<Box<dyn Fn(PreviewLines, Option<PreviewPosition>) + Send + Sync>>;
+
+fn run(
+    rx_preview: Receiver<PreviewEvent>,
+    cache: Arc<SpinLock<PreviewCache>>,
+    on_return: Box<dyn Fn(PreviewLines, Option<PreviewPosition>) + Send + Sync>,
+) {
     let callback = Arc::new(on_return);
     let mut preview_thread: Option<PreviewThread> = None;
-    while let Ok(_event) = rx_preview.recv() {
-        if let Some(mut thread) = preview_thread {
-            thread.kill();
-            preview_thread = None;
+    // the command to re-run on every interval
+    let mut last_command: Option<PreviewCommand> = None;
+    loop {
+        let received = match last_command.as_ref().and_then(|cmd| cmd.interval) {
+            Some(interval) => match rx_preview.recv_timeout(interval) {
+                Err(RecvTimeoutError::Timeout) => Ok(PreviewEvent::Refresh),
+                received => received.map_err(|_| ()),
+            },
+            None => rx_preview.recv().map_err(|_| ()),
+        };
+        let mut event = match received {
+            Ok(PreviewEvent::Abort) | Err(_) => return,
+            Ok(event) => event,
+        };
+
+        // don't interrupt the command if it is still running, e.g. `tail -f`
+        if let PreviewEvent::Refresh = event {
+            let running = preview_thread
+                .as_ref()
+                .map(|thread| !thread.stopped.load(Ordering::SeqCst));
+            if running == Some(true) {
+                continue;
+            }
         }
 
-        let mut event = match _event {
-            PreviewEvent::Abort => return,
-            _ => _event,
-        };
+        if let Some(mut thread) = preview_thread.take() {
+            thread.kill();
+        }
 
         // Try to empty the channel. Happens when spamming up/down or typing fast.
-        while let Ok(_event) = rx_preview.try_recv() {
-            event = match _event {
+        while let Ok(next_event) = rx_preview.try_recv() {
+            event = match next_event {
                 PreviewEvent::Abort => return,
-                _ => _event,
+                _ => next_event,
             }
         }
 
         match event {
             PreviewEvent::PreviewCommand(preview_cmd, pos) => {
-                let cmd = &preview_cmd.cmd;
-                if cmd.is_empty() {
+                if preview_cmd.cmd.is_empty() {
+                    last_command = None;
                     continue;
                 }
-
-                let env_command = which::which("env").unwrap_or_else(|_| PathBuf::from("/usr/bin/env"));
-
-                let spawned = Command::new(env_command)
-                    .env("LINES", preview_cmd.lines.to_string())
-                    .env("COLUMNS", preview_cmd.columns.to_string())
-                    .arg("bash")
-                    .arg("-c")
-                    .arg(cmd)
-                    .stdout(Stdio::piped())
-                    .stderr(Stdio::piped())
-                    .spawn();
-
-                match spawned {
-                    Err(err) => {
-                        let mut output = vec![AnsiString::parse(
-                            format!("ERROR: Command failed to spawn.  Error message:\n").as_str(),
-                        )];
-                        output.push(AnsiString::parse(format!("{}", err).as_str()));
-                        callback(output, pos);
-                        preview_thread = None;
-                    }
-                    Ok(spawned) => {
-                        let pid = spawned.id();
-                        let stopped = Arc::new(AtomicBool::new(false));
-                        let stopped_clone = stopped.clone();
-                        let callback_clone = callback.clone();
-                        let thread = thread::spawn(move || {
-                            wait(spawned, move |lines| {
-                                let output = if lines.is_empty() {
-                                    let ret = vec![AnsiString::parse(
-                                        format!("WARN: Command exited successfully, but output was empty.\n").as_str(),
-                                    )];
-                                    ret
-                                } else {
-                                    lines
-                                };
-                                stopped_clone.store(true, Ordering::SeqCst);
-                                callback_clone(output, pos);
-                            })
-                        });
-                        preview_thread = Some(PreviewThread {
-                            pid,
-                            thread: Some(thread),
-                            stopped,
-                        });
-                    }
+                let cached = preview_cmd.cache_key().and_then(|key| cache.lock().get(&key));
+                match cached {
+                    Some(lines) => callback(PreviewLines::All(lines), Some(pos)),
+                    None => preview_thread = spawn_preview(&preview_cmd, Some(pos), callback.clone(), cache.clone()),
+                }
+                last_command = Some(preview_cmd);
+            }
+            PreviewEvent::Refresh => {
+                if let Some(preview_cmd) = last_command.as_ref() {
+                    preview_thread = spawn_preview(preview_cmd, None, callback.clone(), cache.clone());
                 }
             }
             PreviewEvent::PreviewPlainText(text, pos) => {
-                callback(text.lines().map(|line| line.to_string().into()).collect(), pos);
+                last_command = None;
+                let lines = text.lines().map(|line| line.to_string().into()).collect();
+                callback(PreviewLines::All(lines), Some(pos));
             }
             PreviewEvent::PreviewAnsiText(text, pos) => {
+                last_command = None;
                 let mut parser = ANSIParser::default();
                 let color_lines = text.lines().map(|line| parser.parse_ansi(line)).collect();
-                callback(color_lines, pos);
+                callback(PreviewLines::All(color_lines), Some(pos));
             }
-            PreviewEvent::Noop => {}
+            PreviewEvent::Noop => last_command = None,
             PreviewEvent::Abort => return,
         };
     }
 }
 
+fn spawn_preview(
+    preview_cmd: &PreviewCommand,
+    pos: Option<PreviewPosition>,
+    callback: PreviewCallback,
+    cache: Arc<SpinLock<PreviewCache>>,
+) -> Option<PreviewThread> {
+    let env_command = which::which("env").unwrap_or_else(|_| PathBuf::from("/usr/bin/env"));
+
+    let spawned = Command::new(env_command)
+        .env("LINES", preview_cmd.lines.to_string())
+        .env("COLUMNS", preview_cmd.columns.to_string())
+        .arg("bash")
+        .arg("-c")
+        .arg(&preview_cmd.cmd)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .process_group(0)
+        .spawn();
+
+    match spawned {
+        Err(err) => {
+            let mut output = vec![AnsiString::parse(
+                format!("ERROR: Command failed to spawn.  Error message:\n").as_str(),
+            )];
+            output.push(AnsiString::parse(format!("{}", err).as_str()));
+            callback(PreviewLines::All(output), pos);
+            None
+        }
+        Ok(spawned) => {
+            let pid = spawned.id();
+            let stopped = Arc::new(AtomicBool::new(false));
+            let stopped_clone = stopped.clone();
+            let follow = preview_cmd.follow;
+            let cache_key = preview_cmd.cache_key();
+            // in the follow mode, scroll to the last of the `num_lines` lines
+            let position = move |num_lines: usize| {
+                if follow {
+                    Some(PreviewPosition {
+                        v_scroll: Size::Fixed(num_lines + 1),
+                        v_offset: Size::Percent(100),
+                        ..pos.unwrap_or_default()
+                    })
+                } else {
+                    pos
+                }
+            };
+            let thread = thread::spawn(move || {
+                let on_finish = |lines: Vec<AnsiString>| {
+                    let output = if lines.is_empty() {
+                        let ret = vec![AnsiString::parse(
+                            format!("WARN: Command exited successfully, but output was empty.\n").as_str(),
+                        )];
+                        ret
+                    } else {
+                        lines
+                    };
+                    stopped_clone.store(true, Ordering::SeqCst);
+                    let pos = position(output.len());
+                    callback(PreviewLines::All(output), pos);
+                };
+
+                if follow {
+                    // the lines are appended as they arrive, the first ones replace the previous output
+                    let on_update = |lines: Vec<AnsiString>, num_lines: usize| {
+                        let lines = if num_lines > lines.len() {
+                            PreviewLines::More(lines)
+                        } else {
+                            PreviewLines::All(lines)
+                        };
+                        callback(lines, position(num_lines))
+                    };
+                    match stream(spawned, on_update) {
+                        Some((rest, 0)) => on_finish(rest),
+                        Some((rest, num_lines)) => {
+                            stopped_clone.store(true, Ordering::SeqCst);
+                            let pos = position(num_lines + rest.len());
+                            callback(PreviewLines::More(rest), pos);
+                        }
+                        None => {}
+                    }
+                } else {
+                    wait(spawned, |lines, success| {
+                        // a failure might be transient, don't keep it
+                        if let (Some(key), true) = (cache_key.as_ref(), success && !lines.is_empty()) {
+                            cache.lock().insert(key.clone(), &lines);
+                        }
+                        on_finish(lines);
+                    });
+                }
+            });
+            Some(PreviewThread {
+                pid,
+                thread: Some(thread),
+                stopped,
+            })
+        }
+    }
+}
+
+// Read the output as it arrives, `on_update` is called with the new lines and the number of the
+// lines read so far.
+// -> the lines after the last update (the incomplete line, and stderr on failure) and the number of
+// the lines before them, `None` if the command is killed
+fn stream<C>(mut spawned: Child, on_update: C) -> Option<(Vec<AnsiString>, usize)>
+where
+    C: Fn(Vec<AnsiString>, usize),
+{
+    // read stderr aside, so that the command won't be blocked on it
+    let stderr = spawned.stderr.take().map(|mut stderr| {
+        thread::spawn(move || {
+            let mut output = vec![];
+            let _ = stderr.read_to_end(&mut output);
+            output
+        })
+    });
+
+    let mut num_lines = 0;
+    let mut pending = vec![];
+    if let Some(mut stdout) = spawned.stdout.take() {
+        let mut buffer = [0u8; 8192];
+        while let Ok(read) = stdout.read(&mut buffer) {
+            if read == 0 {
+                break;
+            }
+            pending.extend_from_slice(&buffer[..read]);
+
+            // only the complete lines are shown
+            if let Some(end) = pending.iter().rposition(|&byte| byte == b'\n') {
+                let rest = pending.split_off(end + 1);
+                let lines: Vec<AnsiString> = String::from_utf8_lossy(&pending)
+                    .lines()
+                    .map(AnsiString::parse)
+                    .collect();
+                pending = rest;
+                num_lines += lines.len();
+                on_update(lines, num_lines);
+            }
+        }
+    }
+
+    let status = spawned.wait().ok()?;
+    // On Unix it means the process is terminated by a signal
+    status.code()?;
+
+    let mut lines: Vec<AnsiString> = String::from_utf8_lossy(&pending)
+        .lines()
+        .map(AnsiString::parse)
+        .collect();
+    if !status.success() {
+        let stderr = stderr.and_then(|handle| handle.join().ok()).unwrap_or_default();
+        lines.extend(String::from_utf8_lossy(&stderr).lines().map(AnsiString::parse));
+    }
+    Some((lines, num_lines))
+}
+
+// -> the lines of stdout, or stderr on failure, and whether the command succeeded
 fn wait<C>(spawned: std::process::Child, callback: C)
 where
-    C: Fn(Vec<AnsiString>),
+    C: Fn(Vec<AnsiString>, bool),
 {
     let output = spawned.wait_with_output();
 
@@ -576,7 +890,7 @@ where
     });
 
     let lines = out_str.lines().map(AnsiString::parse).collect();
-    callback(lines);
+    callback(lines, output.status.success());
 }
 
 #[derive(Builder, Default, Debug)]
@@ -676,3 +990,167 @@ impl Printer {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    fn command(cmd: &str, follow: bool, interval: Option<Duration>) -> PreviewCommand {
+        PreviewCommand {
+            cmd: cmd.to_string(),
+            lines: 24,
+            columns: 80,
+            follow,
+            interval,
+        }
+    }
+
+    fn no_cache() -> Arc<SpinLock<PreviewCache>> {
+        Arc::new(SpinLock::new(PreviewCache::default()))
+    }
+
+    fn texts(lines: &[AnsiString]) -> Vec<String> {
+        lines.iter().map(|line| line.stripped().to_string()).collect()
+    }
+
+    // the callbacks of the previews as ("all" or "more", lines, vertical scroll)
+    type Calls = Arc<SpinLock<Vec<(&'static str, Vec<String>, Option<usize>)>>>;
+
+    fn recorder() -> (Calls, PreviewCallback) {
+        let calls: Calls = Arc::new(SpinLock::new(Vec::new()));
+        let calls_clone = calls.clone();
+        let callback: PreviewCallback = Arc::new(Box::new(move |lines, pos: Option<PreviewPosition>| {
+            let (kind, lines) = match lines {
+                PreviewLines::All(lines) => ("all", texts(&lines)),
+                PreviewLines::More(lines) => ("more", texts(&lines)),
+            };
+            let v_scroll = pos.and_then(|pos| match pos.v_scroll {
+                Size::Fixed(line) => Some(line),
+                _ => None,
+            });
+            calls_clone.lock().push((kind, lines, v_scroll));
+        }));
+        (calls, callback)
+    }
+
+    fn spawn(cmd: &str) -> Child {
+        Command::new("sh")
+            .arg("-c")
+            .arg(cmd)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_stream() {
+        let updates = SpinLock::new(Vec::new());
+        let rest = stream(
+            spawn("printf 'a\\nb\\n'; sleep 0.1; printf 'c\\nd'"),
+            |lines, num_lines| updates.lock().push((texts(&lines), num_lines)),
+        );
+
+        // the complete lines are passed as they arrive, the incomplete one is left
+        let (rest, num_lines) = rest.unwrap();
+        assert_eq!(texts(&rest), vec!["d"]);
+        assert_eq!(num_lines, 3);
+        let updates = updates.lock();
+        assert!(updates.len() >= 2);
+        assert_eq!(
+            updates.iter().flat_map(|(lines, _)| lines.clone()).collect::<Vec<_>>(),
+            vec!["a", "b", "c"]
+        );
+        assert_eq!(updates.last().unwrap().1, 3);
+
+        // stderr is kept on failure
+        let rest = stream(spawn("printf 'a\\n'; echo oops >&2; exit 1"), |_, _| {});
+        assert_eq!(texts(&rest.unwrap().0), vec!["oops"]);
+    }
+
+    #[test]
+    fn test_follow() {
+        let (calls, callback) = recorder();
+        let cmd = command("printf 'a\\nb\\n'; sleep 0.1; printf 'c\\n'", true, None);
+        let mut thread = spawn_preview(&cmd, None, callback, no_cache()).unwrap();
+        thread.thread.take().unwrap().join().unwrap();
+
+        // the first lines replace the previous output, the next ones are appended, and the view
+        // follows the last line
+        let calls = calls.lock();
+        assert!(calls.len() >= 2);
+        let mut num_lines = 0;
+        for (idx, (kind, lines, v_scroll)) in calls.iter().enumerate() {
+            assert_eq!(*kind, if idx == 0 { "all" } else { "more" });
+            num_lines += lines.len();
+            assert_eq!(*v_scroll, Some(num_lines + 1));
+        }
+        assert_eq!(num_lines, 3);
+    }
+
+    #[test]
+    fn test_interval_waits_for_the_command() {
+        let path = std::env::temp_dir().join(format!("sk-test-interval-{}", std::process::id()));
+        let (calls, callback) = recorder();
+        let (tx, rx) = unbounded();
+        let thread = thread::spawn(move || run(rx, no_cache(), Box::new(move |lines, pos| callback(lines, pos))));
+
+        // the command outlasts the intervals, it is not restarted meanwhile
+        let cmd = format!("echo run >> {}; sleep 1", path.display());
+        let interval = Some(Duration::from_millis(50));
+        let _ = tx.send(PreviewEvent::PreviewCommand(
+            command(&cmd, false, interval),
+            Default::default(),
+        ));
+        thread::sleep(Duration::from_millis(250));
+        let _ = tx.send(PreviewEvent::Abort);
+        thread.join().unwrap();
+
+        let runs = std::fs::read_to_string(&path).unwrap_or_default();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(runs, "run\n");
+        assert!(calls.lock().is_empty());
+    }
+
+    #[test]
+    fn test_kill_process_group() {
+        let (_calls, callback) = recorder();
+        let cmd = command("sleep 2 | cat", false, None);
+        let mut thread = spawn_preview(&cmd, None, callback, no_cache()).unwrap();
+        thread::sleep(Duration::from_millis(100));
+
+        // `cat` is killed as well, or it would keep the output open until `sleep` exits
+        let start = Instant::now();
+        thread.kill();
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+
+    fn key(cmd: &str) -> PreviewKey {
+        (cmd.to_string(), 80, 24)
+    }
+
+    #[test]
+    fn test_preview_cache() {
+        let mut cache = PreviewCache {
+            capacity: 2,
+            ..Default::default()
+        };
+        cache.insert(key("a"), &[AnsiString::parse("A")]);
+        cache.insert(key("b"), &[AnsiString::parse("B")]);
+
+        // "a" becomes the most recently used, so "b" is evicted
+        assert_eq!(cache.get(&key("a")).unwrap()[0].stripped(), "A");
+        cache.insert(key("c"), &[AnsiString::parse("C")]);
+        assert!(cache.get(&key("b")).is_none());
+        assert!(cache.get(&key("a")).is_some());
+        assert!(cache.get(&("c".to_string(), 40, 24)).is_none());
+
+        cache.clear();
+        assert!(cache.get(&key("c")).is_none());
+
+        let mut disabled = PreviewCache::default();
+        disabled.insert(key("a"), &[]);
+        assert!(disabled.get(&key("a")).is_none());
+    }
+}
@@ -27,6 +27,9 @@ pub struct Query {
     replstr: String,
     query_prompt: String,
     cmd_prompt: String,
+    // the placeholders of the empty queries
+    query_ghost: String,
+    cmd_ghost: String,
 
     cmd_history_before: Vec<String>,
     cmd_history_after: Vec<String>,
@@ -52,6 +55,8 @@ impl Query {
             replstr: "{}".to_string(),
             query_prompt: "> ".to_string(),
             cmd_prompt: "c> ".to_string(),
+            query_ghost: String::new(),
+            cmd_ghost: String::new(),
 
             cmd_history_before: Vec::new(),
             cmd_history_after: Vec::new(),
@@ -132,6 +137,14 @@ impl Query {
             self.cmd_prompt = cmd_prompt.to_string();
         }
 
+        if let Some(ghost) = options.ghost {
+            self.query_ghost = ghost.to_string();
+        }
+
+        if let Some(cmd_ghost) = options.cmd_ghost {
+            self.cmd_ghost = cmd_ghost.to_string();
+        }
+
         self.fz_query_history_before = options.query_history.to_vec();
         self.cmd_history_before = options.cmd_history.to_vec();
     }
@@ -197,6 +210,20 @@ impl Query {
         }
     }
 
+    /// the placeholder of the current mode, while its query is empty
+    fn get_ghost(&self) -> &str {
+        let (before, after, ghost) = match self.mode {
+            QueryMode::Cmd => (&self.cmd_before, &self.cmd_after, &self.cmd_ghost),
+            QueryMode::Query => (&self.fz_query_before, &self.fz_query_after, &self.query_ghost),
+        };
+
+        if before.is_empty() && after.is_empty() {
+            ghost
+        } else {
+            ""
+        }
+    }
+
     fn get_query_ref(&mut self) -> (&mut Vec<char>, &mut Vec<char>) {
         match self.mode {
             QueryMode::Query => (&mut self.fz_query_before, &mut self.fz_query_after),
@@ -553,6 +580,7 @@ impl Draw for Query {
         let before_width = canvas.print_with_attr(0, prompt_width, &before, self.theme.query())?;
         let col = prompt_width + before_width;
         canvas.print_with_attr(0, col, &after, self.theme.query())?;
+        canvas.print_with_attr(0, col, self.get_ghost(), self.theme.ghost())?;
         canvas.set_cursor(0, col)?;
         canvas.show_cursor(true)?;
         Ok(())
@@ -564,7 +592,63 @@ impl Widget<Event> for Query {
         let before = self.get_before();
         let after = self.get_after();
         let prompt = self.get_prompt();
-        (Some(prompt.width() + before.width() + after.width() + 1), None)
+        let ghost = self.get_ghost();
+        (
+            Some(prompt.width() + before.width() + after.width() + ghost.width() + 1),
+            None,
+        )
+    }
+}
+
+/// The prompt alone, for layouts that put something between the prompt and the query
+pub struct QueryPrompt<'a>(&'a Query);
+
+/// The query (and cursor) without the prompt
+pub struct QueryInput<'a>(&'a Query);
+
+impl Query {
+    pub fn prompt_widget(&self) -> QueryPrompt<'_> {
+        QueryPrompt(self)
+    }
+
+    pub fn input_widget(&self) -> QueryInput<'_> {
+        QueryInput(self)
+    }
+}
+
+impl<'a> Draw for QueryPrompt<'a> {
+    fn draw(&self, canvas: &mut dyn Canvas) -> DrawResult<()> {
+        clear_canvas(canvas)?;
+        canvas.print_with_attr(0, 0, self.0.get_prompt(), self.0.theme.prompt())?;
+        Ok(())
+    }
+}
+
+impl<'a> Widget<Event> for QueryPrompt<'a> {
+    fn size_hint(&self) -> (Option<usize>, Option<usize>) {
+        (Some(self.0.get_prompt().width()), None)
+    }
+}
+
+impl<'a> Draw for QueryInput<'a> {
+    fn draw(&self, canvas: &mut dyn Canvas) -> DrawResult<()> {
+        let before = self.0.get_before();
+        let after = self.0.get_after();
+        clear_canvas(canvas)?;
+
+        let col = canvas.print_with_attr(0, 0, &before, self.0.theme.query())?;
+        canvas.print_with_attr(0, col, &after, self.0.theme.query())?;
+        canvas.print_with_attr(0, col, self.0.get_ghost(), self.0.theme.ghost())?;
+        canvas.set_cursor(0, col)?;
+        canvas.show_cursor(true)?;
+        Ok(())
+    }
+}
+
+impl<'a> Widget<Event> for QueryInput<'a> {
+    fn size_hint(&self) -> (Option<usize>, Option<usize>) {
+        let width = self.0.get_before().width() + self.0.get_after().width() + self.0.get_ghost().width();
+        (Some(width + 1), None)
     }
 }
 
@@ -612,4 +696,23 @@ mod test {
         query.act_backward_delete_char();
         assert_eq!(query.get_fz_query(), "");
     }
+
+    #[test]
+    fn test_ghost() {
+        let options = crate::SkimOptions {
+            ghost: Some("search"),
+            cmd_ghost: Some("command"),
+            ..Default::default()
+        };
+        let mut query = Query::from_options(&options);
+        assert_eq!(query.get_ghost(), "search");
+
+        query.act_add_char('a');
+        assert_eq!(query.get_ghost(), "");
+        query.act_backward_delete_char();
+        assert_eq!(query.get_ghost(), "search");
+
+        query.act_query_toggle_interactive();
+        assert_eq!(query.get_ghost(), "command");
+    }
 }
@@ -3,11 +3,11 @@ use crate::global::mark_new_run;
 ///!
 ///! After reading in a line, reader will save an item into the pool(items)
 use crate::options::SkimOptions;
+use crate::wait_group::WaitGroup;
 use crate::{SkimItem, SkimItemReceiver};
 use crossbeam_channel::{unbounded, Select, Sender};
 use std::cell::RefCell;
 use std::rc::Rc;
-use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::{Arc, RwLock, Weak};
 use std::thread::{self, sleep, JoinHandle};
 use std::time::Duration;
@@ -25,19 +25,19 @@ pub trait CommandCollector {
     /// - a channel sender, any message send would mean to terminate the `cmd` process (for now).
     ///
     /// Internally, the command collector may start several threads(components), the collector
-    /// should add `1` on every thread creation and sub `1` on thread termination. reader would use
-    /// this information to determine whether the collector had stopped or not.
+    /// should call `add(1)` before every thread creation and `done()` on thread termination.
+    /// reader would use this information to determine(or wait until) the collector had stopped.
     fn invoke(
         &mut self,
         cmd: &str,
-        components_to_stop: Arc<AtomicUsize>,
+        components_to_stop: Arc<WaitGroup>,
     ) -> (SkimItemReceiver, Sender<i32>, Option<JoinHandle<()>>);
 }
 
 pub struct ReaderControl {
     tx_interrupt: Sender<i32>,
     tx_interrupt_cmd: Option<Sender<i32>>,
-    components_to_stop: Arc<AtomicUsize>,
+    components_to_stop: Arc<WaitGroup>,
     items: Arc<RwLock<Vec<Arc<dyn SkimItem>>>>,
     thread_reader: Option<JoinHandle<()>>,
     thread_ingest: Option<JoinHandle<()>>,
@@ -52,10 +52,7 @@ impl Drop for ReaderControl {
 
 impl ReaderControl {
     pub fn kill(&mut self) {
-        debug!(
-            "kill reader, components before: {}",
-            self.components_to_stop.load(Ordering::SeqCst)
-        );
+        debug!("kill reader, components before: {}", self.components_to_stop.count());
 
         let _ = self.tx_interrupt_cmd.as_ref().map(|tx| tx.send(1));
         let _ = self.tx_interrupt.send(1);
@@ -73,7 +70,8 @@ impl ReaderControl {
         #[cfg(target_env = "gnu")]
         malloc_trim();
 
-        while self.components_to_stop.load(Ordering::SeqCst) != 0 {}
+        // block until the threads of the collector that we have no handle of are stopped
+        self.components_to_stop.wait();
     }
 
     pub fn take(&mut self) -> Vec<Arc<dyn SkimItem>> {
@@ -86,7 +84,7 @@ impl ReaderControl {
     }
 
     pub fn all_stopped(&self) -> bool {
-        self.components_to_stop.load(Ordering::SeqCst) == 0
+        self.components_to_stop.all_stopped()
     }
 
     pub fn is_empty(&self) -> bool {
@@ -123,7 +121,7 @@ impl Reader {
     pub fn run(&mut self, cmd: &str) -> ReaderControl {
         mark_new_run(cmd);
 
-        let components_to_stop: Arc<AtomicUsize> = Arc::new(AtomicUsize::new(0));
+        let components_to_stop: Arc<WaitGroup> = Arc::new(WaitGroup::new());
         let items_strong = Arc::new(RwLock::new(Vec::with_capacity(ITEMS_INITIAL_CAPACITY)));
         let items_weak = Arc::downgrade(&items_strong);
 
@@ -150,18 +148,16 @@ impl Reader {
 }
 
 fn collect_item(
-    components_to_stop: Arc<AtomicUsize>,
+    components_to_stop: Arc<WaitGroup>,
     rx_item: SkimItemReceiver,
     items_weak: Weak<RwLock<Vec<Arc<dyn SkimItem>>>>,
 ) -> (Sender<i32>, JoinHandle<()>) {
     let (tx_interrupt, rx_interrupt) = unbounded();
 
-    let started = Arc::new(AtomicBool::new(false));
-    let started_clone = started.clone();
+    // register the component before it is started, so that the reader is never seen as stopped
+    components_to_stop.add(1);
     let thread_reader = thread::spawn(move || {
         debug!("reader: collect_item start");
-        components_to_stop.fetch_add(1, Ordering::SeqCst);
-        started_clone.store(true, Ordering::SeqCst); // notify parent that it is started
 
         let mut sel = Select::new();
         let item_channel = sel.recv(&rx_item);
@@ -203,13 +199,9 @@ fn collect_item(
             }
         }
 
-        components_to_stop.fetch_sub(1, Ordering::SeqCst);
+        components_to_stop.done();
         debug!("reader: collect_item stop");
     });
 
-    while !started.load(Ordering::SeqCst) {
-        // busy waiting for the thread to start. (components_to_stop is added)
-    }
-
     (tx_interrupt, thread_reader)
 }
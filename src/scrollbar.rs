@@ -0,0 +1,84 @@
+//! Scrollbars of the result list and the preview window
+//!
+//! The scrollbar takes the last column of the widget, its thumb shows the position and the
+//! proportion of the visible part of the content.
+use std::cmp::{max, min};
+
+use tuikit::prelude::*;
+
+pub const DEFAULT_SCROLLBAR: char = '│';
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Scrollbar {
+    // the number of lines (or items) of the content
+    total: usize,
+    // the number of lines visible at once
+    visible: usize,
+    // the first visible line
+    offset: usize,
+    // the number of rows of the scrollbar
+    height: usize,
+}
+
+impl Scrollbar {
+    /// `None` if the whole content is visible
+    pub fn new(total: usize, visible: usize, offset: usize, height: usize) -> Option<Self> {
+        if height == 0 || total <= visible {
+            return None;
+        }
+
+        Some(Self {
+            total,
+            visible,
+            offset,
+            height,
+        })
+    }
+
+    /// the first row (from the start of the content) and the number of rows of the thumb
+    pub fn thumb(&self) -> (usize, usize) {
+        let len = min(self.height, max(1, self.height * self.visible / self.total));
+        let scrollable = self.total - self.visible;
+        let start = (self.height - len) * min(self.offset, scrollable) / scrollable;
+        (start, len)
+    }
+
+    /// the line of the content at the row of the scrollbar (from the start of the content)
+    pub fn offset_at(&self, row: usize) -> usize {
+        min(self.total - 1, row * self.total / self.height)
+    }
+
+    /// draw the thumb in the column `col`, the rows are counted from the bottom if `bottom_up`
+    pub fn draw(&self, canvas: &mut dyn Canvas, col: usize, glyph: char, attr: Attr, bottom_up: bool) {
+        let (start, len) = self.thumb();
+        for row in start..start + len {
+            let row = if bottom_up { self.height - 1 - row } else { row };
+            let _ = canvas.put_char_with_attr(row, col, glyph, attr);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_thumb() {
+        assert_eq!(Scrollbar::new(10, 10, 0, 10), None);
+
+        let scrollbar = Scrollbar::new(100, 10, 0, 10).unwrap();
+        assert_eq!(scrollbar.thumb(), (0, 1));
+        let scrollbar = Scrollbar::new(100, 10, 90, 10).unwrap();
+        assert_eq!(scrollbar.thumb(), (9, 1));
+        let scrollbar = Scrollbar::new(20, 10, 5, 10).unwrap();
+        assert_eq!(scrollbar.thumb(), (2, 5));
+    }
+
+    #[test]
+    fn test_offset_at() {
+        let scrollbar = Scrollbar::new(100, 10, 0, 10).unwrap();
+        assert_eq!(scrollbar.offset_at(0), 0);
+        assert_eq!(scrollbar.offset_at(5), 50);
+        assert_eq!(scrollbar.offset_at(20), 99);
+    }
+}
@@ -3,23 +3,47 @@ use std::cmp::min;
 use std::collections::BTreeMap;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
+use tuikit::canvas::BoundedCanvas;
 use tuikit::prelude::{Event as TermEvent, *};
 
 ///! Handle the selections of items
+use crate::ansi::AnsiString;
 use crate::event::{Event, EventHandler, UpdateScreen};
 use crate::global::current_run_num;
+use crate::group::{GroupOrder, GroupRow, Groups};
 use crate::item::MatchedItem;
 use crate::orderedvec::OrderedVec;
+use crate::scrollbar::{Scrollbar, DEFAULT_SCROLLBAR};
+use crate::tabular::{Tabular, DEFAULT_TABULAR_MAX_WIDTH};
 use crate::theme::{ColorTheme, DEFAULT_THEME};
+use crate::tree::{Tree, TreeRow};
 use crate::util::clear_canvas;
-use crate::util::{print_item, reshape_string, LinePrinter};
+use crate::util::{densest_match_cluster, print_item, reshape_string, LinePrinter};
 use crate::{DisplayContext, MatchRange, Matches, Selector, SkimItem, SkimOptions};
 use regex::Regex;
-use unicode_width::UnicodeWidthStr;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 type ItemIndex = (u32, u32);
 
+// with `--top-k=auto`, the number of items kept ordered beyond the screen height
+const TOP_K_SCROLL_MARGIN: usize = 100;
+const DEFAULT_HSCROLL_OFF: usize = 10;
+const DEFAULT_JUMP_LABELS: &str = "abcdefghijklmnopqrstuvwxyz";
+const DELIMITER_STR: &str = r"[\t\n ]+";
+// how often the tree or the groups are rebuilt while the results stream in
+const ROWS_REBUILD_INTERVAL: Duration = Duration::from_millis(200);
+
+/// What to do once a row is picked by its jump label
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JumpMode {
+    /// move the cursor to the row (and toggle it in multi-selection mode)
+    Jump,
+    /// move the cursor to the row and accept it
+    JumpAccept,
+}
+
 pub struct Selection {
     // all items
     items: OrderedVec<MatchedItem>,
@@ -41,14 +65,45 @@ pub struct Selection {
     // line No.
     line_cursor: usize,
     hscroll_offset: i64,
+    // columns of context to keep around the match on horizontal shifts
+    hscroll_off: usize,
     keep_right: bool,
     skip_to_pattern: Option<Regex>,
     height: AtomicUsize,
     tabstop: usize,
+    // derive the number of ordered items from the screen height
+    auto_top_k: bool,
+
+    // glyphs in front of the items, padded to the same width
+    pointer: String,
+    pointer_blank: String,
+    marker: String,
+    unmarked: String,
+
+    // the labels of the visible rows, from the cursor line 0, displayed in the jump mode
+    jump_labels: Vec<char>,
+    jump: Option<JumpMode>,
+
+    // the maximum number of rows of an item with multiple lines, 0 for no limit
+    max_lines: usize,
+    // the glyph of the scrollbar, `None` for no scrollbar
+    scrollbar: Option<char>,
+    // align the fields of the items in columns
+    tabular: Option<Tabular>,
+    // group the items by their path segments
+    tree: Option<Tree>,
+    // move the cursor to the best match once the new results arrive, instead of its ancestors
+    tree_cursor_to_best: bool,
+    // the items changed since the tree or the groups were built
+    rows_dirty: bool,
+    rows_built_at: Instant,
+    // list the items under the headers of their groups
+    groups: Option<Groups>,
 
     // Options
     multi_selection: bool,
     reverse: bool,
+    cycle: bool,
     no_hscroll: bool,
     theme: Arc<ColorTheme>,
 
@@ -83,12 +138,29 @@ impl Selection {
             item_cursor: 0,
             line_cursor: 0,
             hscroll_offset: 0,
+            hscroll_off: DEFAULT_HSCROLL_OFF,
             keep_right: false,
             skip_to_pattern: None,
             height: AtomicUsize::new(0),
             tabstop: 8,
+            auto_top_k: false,
+            pointer: ">".to_string(),
+            pointer_blank: " ".to_string(),
+            marker: ">".to_string(),
+            unmarked: " ".to_string(),
+            jump_labels: DEFAULT_JUMP_LABELS.chars().collect(),
+            jump: None,
+            max_lines: 0,
+            scrollbar: None,
+            tabular: None,
+            tree: None,
+            tree_cursor_to_best: false,
+            rows_dirty: false,
+            rows_built_at: Instant::now(),
+            groups: None,
             multi_selection: false,
             reverse: false,
+            cycle: false,
             no_hscroll: false,
             theme: Arc::new(*DEFAULT_THEME),
             latest_select_run_num: 0,
@@ -112,10 +184,49 @@ impl Selection {
             self.reverse = true;
         }
 
+        if let Some(labels) = options.jump_labels {
+            if !labels.is_empty() {
+                self.jump_labels = labels.chars().collect();
+            }
+        }
+
+        if options.cycle {
+            self.cycle = true;
+        }
+
+        if let Some(scrollbar) = options.scrollbar {
+            self.scrollbar = Some(scrollbar.chars().next().unwrap_or(DEFAULT_SCROLLBAR));
+        }
+
+        if let Some(max_lines) = options.multi_line {
+            self.max_lines = max_lines.parse::<usize>().unwrap_or(0);
+        }
+
+        if let Some(max_width) = options.tabular {
+            let delimiter = options
+                .delimiter
+                .and_then(|delimiter| Regex::new(delimiter).ok())
+                .unwrap_or_else(|| Regex::new(DELIMITER_STR).unwrap());
+            let max_width = max_width.parse::<usize>().unwrap_or(DEFAULT_TABULAR_MAX_WIDTH);
+            self.tabular = Some(Tabular::new(delimiter, max_width));
+        }
+
+        if let Some(separator) = options.tree {
+            self.tree = Some(Tree::new(separator));
+        } else if let Some(order) = options.groups {
+            // `SkimOptions::validate` rejects the unknown orders
+            let order = GroupOrder::parse(order).unwrap_or(GroupOrder::Rank);
+            self.groups = Some(Groups::new(order).headers_after(!self.reverse));
+        }
+
         if options.no_hscroll {
             self.no_hscroll = true;
         }
 
+        if let Some(hscroll_off) = options.hscroll_off {
+            self.hscroll_off = hscroll_off.parse::<usize>().unwrap_or(DEFAULT_HSCROLL_OFF);
+        }
+
         if let Some(tabstop_str) = options.tabstop {
             let tabstop = tabstop_str.parse::<usize>().unwrap_or(8);
             self.tabstop = max(1, tabstop);
@@ -129,12 +240,49 @@ impl Selection {
             self.items.nosort(true);
         }
 
+        match options.top_k {
+            // the tree and the groups order all the items anyway
+            _ if self.has_rows() => {}
+            Some("auto") => {
+                self.auto_top_k = true;
+                self.items.top_k(Some(TOP_K_SCROLL_MARGIN));
+            }
+            // `SkimOptions::validate` rejects the other values
+            Some(top_k) => {
+                self.items.top_k(top_k.parse::<usize>().ok());
+            }
+            None => {}
+        }
+
         if !options.skip_to_pattern.is_empty() {
             self.skip_to_pattern = Regex::new(options.skip_to_pattern).ok();
         }
 
         self.keep_right = options.keep_right;
         self.selector = options.selector.clone();
+
+        if options.pointer.is_some() || options.marker.is_some() || options.unmarked.is_some() {
+            self.set_glyphs(
+                options.pointer.unwrap_or(">"),
+                options.marker.unwrap_or(">"),
+                options.unmarked.unwrap_or(" "),
+            );
+        }
+    }
+
+    fn set_glyphs(&mut self, pointer: &str, marker: &str, unmarked: &str) {
+        let pointer_width = pointer.width_cjk();
+        self.pointer = pointer.to_string();
+        self.pointer_blank = " ".repeat(pointer_width);
+
+        let marker_width = max(marker.width_cjk(), unmarked.width_cjk());
+        self.marker = pad_to_width(marker, marker_width);
+        self.unmarked = pad_to_width(unmarked, marker_width);
+    }
+
+    /// the width of the pointer and the marker in front of the items
+    fn prefix_width(&self) -> usize {
+        self.pointer_blank.len() + self.unmarked.width_cjk()
     }
 
     pub fn theme(mut self, theme: Arc<ColorTheme>) -> Self {
@@ -154,23 +302,170 @@ impl Selection {
             self.pre_select(&items);
         }
 
+        let height = self.height.load(Ordering::Relaxed);
+        if self.auto_top_k {
+            self.items.top_k(Some(height + TOP_K_SCROLL_MARGIN));
+        }
+
         self.items.append(items);
         self.pre_selected_watermark = max(self.pre_selected_watermark, self.items.len());
 
+        // the tree and the groups are built from all the items, see `sync_rows`
+        self.rows_dirty = self.has_rows();
+        if !self.rows_dirty {
+            self.rebuild_tree();
+            self.fit_cursor_to_rows();
+        }
+    }
+
+    // whether the rows are built from the items, i.e. the tree or the groups
+    fn has_rows(&self) -> bool {
+        self.tree.is_some() || self.groups.is_some()
+    }
+
+    /// build the tree or the groups if the items changed since, e.g. on the final results or
+    /// before moving the cursor. While the results stream in, the heartbeats rebuild them at most
+    /// every `ROWS_REBUILD_INTERVAL`, as every build orders all the items.
+    pub fn sync_rows(&mut self) {
+        if self.rows_dirty {
+            self.rows_dirty = false;
+            self.rows_built_at = Instant::now();
+            self.rebuild_tree();
+            self.fit_cursor_to_rows();
+        }
+    }
+
+    // keep the cursor within the rows after they changed
+    fn fit_cursor_to_rows(&mut self) {
         let height = self.height.load(Ordering::Relaxed);
-        if self.items.len() <= self.line_cursor {
+        let num_rows = self.num_rows();
+        if num_rows <= self.line_cursor {
             // if not enough items, move cursor down
-            self.line_cursor = max(min(self.items.len(), height), 1) - 1;
+            self.line_cursor = max(min(num_rows, height), 1) - 1;
         }
 
-        if self.items.len() <= self.line_cursor + self.item_cursor {
+        if num_rows <= self.line_cursor + self.item_cursor {
             // if not enough items, scroll the cursor a page down
-            self.item_cursor = max(self.items.len(), height) - height;
+            self.item_cursor = max(num_rows, height) - height;
+        }
+
+        if self.tree_cursor_to_best && !self.items.is_empty() {
+            self.tree_cursor_to_best = false;
+            let best = self
+                .tree
+                .as_ref()
+                .filter(|tree| tree.is_expand_all())
+                .zip(self.items.get(0))
+                .and_then(|(tree, item)| tree.position(&item));
+            if let Some(index) = best {
+                self.act_scroll_to(index);
+            }
         }
+
+        self.skip_group_header(0);
     }
 
     pub fn clear(&mut self) {
         self.items.clear();
+        if let Some(tabular) = self.tabular.as_ref() {
+            tabular.reset();
+        }
+        self.rows_dirty = self.has_rows();
+        self.tree_cursor_to_best = self.tree.is_some();
+    }
+
+    // the number of rows of the list, i.e. the items, or the visible nodes in the tree mode
+    fn num_rows(&self) -> usize {
+        match (self.tree.as_ref(), self.groups.as_ref()) {
+            (Some(tree), _) => tree.len(),
+            (None, Some(groups)) => groups.len(),
+            (None, None) => self.items.len(),
+        }
+    }
+
+    // the item at the row, `None` for group headers
+    fn row(&self, index: usize) -> Option<MatchedItem> {
+        match (self.tree.as_ref(), self.groups.as_ref()) {
+            (Some(tree), _) => tree.get(index).map(|row| row.item.clone()),
+            (None, Some(groups)) => match groups.get(index) {
+                Some(GroupRow::Item(item)) => Some(item.clone()),
+                _ => None,
+            },
+            (None, None) => self.items.get(index),
+        }
+    }
+
+    // the number of screen rows taken by the row
+    fn row_height(&self, index: usize) -> Option<usize> {
+        if self.is_group_header(index) {
+            Some(1)
+        } else {
+            self.row(index).map(|item| self.item_rows(&item))
+        }
+    }
+
+    fn is_group_header(&self, index: usize) -> bool {
+        self.groups.as_ref().map(|groups| groups.is_header(index)) == Some(true)
+    }
+
+    // the group headers are not selectable, step over the one under the cursor in the direction
+    // of the move (`diff` as in `act_move_line_cursor`)
+    fn skip_group_header(&mut self, diff: i32) {
+        let cursor = self.item_cursor + self.line_cursor;
+        if !self.is_group_header(cursor) {
+            return;
+        }
+
+        let index_diff = if self.reverse { -diff } else { diff };
+        let step = if (index_diff >= 0 && cursor + 1 < self.num_rows()) || cursor == 0 {
+            1
+        } else {
+            -1
+        };
+        if self.height.load(Ordering::Relaxed) == 0 {
+            // not drawn yet, nothing to scroll
+            let index = (cursor as i32 + step) as usize;
+            self.item_cursor = min(self.item_cursor, index);
+            self.line_cursor = index - self.item_cursor;
+        } else {
+            self.act_move_line_cursor(if self.reverse { -step } else { step });
+        }
+    }
+
+    fn rebuild_tree(&mut self) {
+        if let Some(tree) = self.tree.as_mut() {
+            tree.build(self.items.iter());
+        } else if let Some(groups) = self.groups.as_mut() {
+            groups.build(self.items.iter());
+        }
+    }
+
+    /// show all the nodes of the tree, e.g. while a query is active, effective on the next items
+    pub fn expand_tree(&mut self, expand_all: bool) {
+        if let Some(tree) = self.tree.as_mut() {
+            tree.set_expand_all(expand_all);
+        }
+    }
+
+    /// collapse the directory under the cursor (or the parent of the item) in the tree mode
+    pub fn act_collapse(&mut self) {
+        self.sync_rows();
+        let cursor = self.item_cursor + self.line_cursor;
+        if let Some(index) = self.tree.as_mut().and_then(|tree| tree.collapse(cursor)) {
+            self.rebuild_tree();
+            self.act_scroll_to(index);
+        }
+    }
+
+    /// expand the directory under the cursor in the tree mode
+    pub fn act_expand(&mut self) {
+        self.sync_rows();
+        let cursor = self.item_cursor + self.line_cursor;
+        if let Some(tree) = self.tree.as_mut() {
+            if tree.expand(cursor) {
+                self.rebuild_tree();
+            }
+        }
     }
 
     fn pre_select(&mut self, items: &[MatchedItem]) {
@@ -199,7 +494,7 @@ impl Selection {
 
         let mut line_cursor = self.line_cursor as i32;
         let mut item_cursor = self.item_cursor as i32;
-        let item_len = self.items.len() as i32;
+        let item_len = self.num_rows() as i32;
 
         let height = self.height.load(Ordering::Relaxed) as i32;
 
@@ -220,28 +515,167 @@ impl Selection {
 
         self.item_cursor = item_cursor as usize;
         self.line_cursor = line_cursor as usize;
+        self.fit_cursor_item();
+        self.skip_group_header(diff);
+    }
+
+    // the number of rows the item takes on the screen
+    fn item_rows(&self, item: &MatchedItem) -> usize {
+        if self.max_lines == 1 {
+            return 1;
+        }
+
+        let lines = max(1, item.upgrade_infallible().text().lines().count());
+        if self.max_lines == 0 {
+            lines
+        } else {
+            min(lines, self.max_lines)
+        }
+    }
+
+    // items with multiple lines take more than one row, scroll until the current item fits in the screen
+    fn fit_cursor_item(&mut self) {
+        if self.max_lines == 1 {
+            return;
+        }
+
+        let height = self.height.load(Ordering::Relaxed);
+        while self.line_cursor > 0 {
+            let rows: usize = (self.item_cursor..=self.item_cursor + self.line_cursor)
+                .filter_map(|idx| self.row_height(idx))
+                .sum();
+            if rows <= height {
+                break;
+            }
+            self.item_cursor += 1;
+            self.line_cursor -= 1;
+        }
+    }
+
+    // the offset (from `item_cursor`) of the item displayed at the row, rows are counted from the query.
+    // Rows beyond the last item continue to count one row per item.
+    fn item_offset_at_row(&self, row: usize) -> usize {
+        let mut used_rows = 0;
+        let mut offset = 0;
+        while let Some(rows) = self.row_height(self.item_cursor + offset) {
+            used_rows += rows;
+            if row < used_rows {
+                return offset;
+            }
+            offset += 1;
+        }
+        offset + row - used_rows
+    }
+
+    // the number of items (from `item_cursor`) visible in the screen
+    fn visible_items(&self) -> usize {
+        self.visible_items_in(self.height.load(Ordering::Relaxed))
+    }
+
+    fn visible_items_in(&self, height: usize) -> usize {
+        min(
+            self.item_offset_at_row(height),
+            self.num_rows() - min(self.item_cursor, self.num_rows()),
+        )
+    }
+
+    // `None` if disabled or all the items are visible
+    fn get_scrollbar(&self, height: usize) -> Option<Scrollbar> {
+        self.scrollbar?;
+        Scrollbar::new(self.num_rows(), self.visible_items_in(height), self.item_cursor, height)
+    }
+
+    pub fn act_scroll_to(&mut self, item_index: usize) {
+        let diff = item_index as i32 - (self.item_cursor + self.line_cursor) as i32;
+        self.act_move_line_cursor(if self.reverse { -diff } else { diff });
+    }
+
+    // like `act_move_line_cursor`, but with `--cycle`, moving beyond the first/last item wraps
+    // around to the other end. It works on the item indices, whose order already respects `tac`.
+    pub fn act_move_line_cursor_cyclic(&mut self, diff: i32) {
+        let item_len = self.num_rows() as i32;
+        if !self.cycle || item_len == 0 {
+            return self.act_move_line_cursor(diff);
+        }
+
+        // the diff of the item index, > 0 means towards the last item
+        let index_diff = if self.reverse { -diff } else { diff };
+        let cursor = (self.item_cursor + self.line_cursor) as i32;
+        // the first or the last row might be a group header
+        let is_item = |&row: &i32| !self.is_group_header(row as usize);
+        let first = (0..item_len).find(is_item).unwrap_or(0);
+        let last = (0..item_len).rev().find(is_item).unwrap_or(item_len - 1);
+
+        // only wrap when the cursor is already at the end, so that a page move stops at the last item first
+        let index_diff = if index_diff > 0 && cursor == last {
+            first - cursor
+        } else if index_diff < 0 && cursor == first {
+            last - cursor
+        } else {
+            index_diff
+        };
+
+        self.act_move_line_cursor(if self.reverse { -index_diff } else { index_diff });
+    }
+
+    pub fn jump_mode(&self) -> Option<JumpMode> {
+        self.jump
+    }
+
+    /// leave the jump mode on the key, which picks the row by its label, any other key cancels it.
+    /// Return the event to handle instead of the key's, i.e. accept the row in the `jump-accept` mode
+    pub fn act_jump_key(&mut self, key: Key) -> Event {
+        let mode = self.jump;
+        let label = match key {
+            Key::Char(ch) => Some(ch),
+            _ => None,
+        };
+        if self.act_jump(label) && mode == Some(JumpMode::JumpAccept) {
+            Event::EvActAccept(None)
+        } else {
+            Event::EvActIgnore
+        }
+    }
+
+    /// leave the jump mode, move the cursor to the row labeled `label` if it is visible.
+    /// Return whether the cursor is moved.
+    fn act_jump(&mut self, label: Option<char>) -> bool {
+        self.sync_rows();
+        let mode = self.jump.take();
+        let offset = label.and_then(|label| self.jump_labels.iter().position(|&ch| ch == label));
+
+        match offset {
+            Some(offset) if offset < self.visible_items() && !self.is_group_header(self.item_cursor + offset) => {
+                self.line_cursor = offset;
+                if mode == Some(JumpMode::Jump) {
+                    self.act_toggle();
+                }
+                true
+            }
+            _ => false,
+        }
     }
 
     pub fn act_select_screen_row(&mut self, rows_to_top: usize) {
         let height = self.height.load(Ordering::Relaxed);
         let diff = if self.reverse {
-            self.line_cursor as i32 - rows_to_top as i32
+            self.line_cursor as i32 - self.item_offset_at_row(rows_to_top) as i32
         } else {
-            height as i32 - rows_to_top as i32 - 1 - self.line_cursor as i32
+            let row = max(height, rows_to_top + 1) - rows_to_top - 1;
+            self.item_offset_at_row(row) as i32 - self.line_cursor as i32
         };
         self.act_move_line_cursor(diff);
     }
 
     #[allow(clippy::map_entry)]
     pub fn act_toggle(&mut self) {
-        if !self.multi_selection || self.items.is_empty() {
+        if !self.multi_selection || self.num_rows() == 0 {
             return;
         }
 
         let cursor = self.item_cursor + self.line_cursor;
         let current_item = self
-            .items
-            .get(cursor)
+            .row(cursor)
             .unwrap_or_else(|| panic!("model:act_toggle: failed to get item {}", cursor));
 
         let index = (current_run_num(), current_item.item_idx);
@@ -311,11 +745,10 @@ impl Selection {
 
         let mut item_indices: Vec<usize> = self.selected.keys().map(|(_run, idx)| *idx as usize).collect();
 
-        if select_cursor && !self.items.is_empty() {
+        if select_cursor && self.num_rows() > 0 {
             let cursor = self.item_cursor + self.line_cursor;
             let current_item = self
-                .items
-                .get(cursor)
+                .row(cursor)
                 .unwrap_or_else(|| panic!("model:act_output: failed to get item {}", cursor));
             item_indices.push(cursor);
             selected.push(current_item.clone());
@@ -342,7 +775,7 @@ impl Selection {
 
     pub fn get_current_item(&self) -> Option<Arc<dyn SkimItem>> {
         let item_idx = self.get_current_item_idx();
-        self.items.get(item_idx).map(|item| item.upgrade_infallible())
+        self.row(item_idx).map(|item| item.upgrade_infallible())
     }
 
     pub fn get_hscroll_offset(&self) -> i64 {
@@ -371,12 +804,18 @@ impl Selection {
 impl EventHandler for Selection {
     fn handle(&mut self, event: &Event) -> UpdateScreen {
         use crate::event::Event::*;
+        match event {
+            EvHeartBeat if self.rows_built_at.elapsed() < ROWS_REBUILD_INTERVAL => {}
+            // any other event acts on the rows as shown
+            _ => self.sync_rows(),
+        }
+
         match event {
             EvActUp(diff) => {
-                self.act_move_line_cursor(*diff);
+                self.act_move_line_cursor_cyclic(*diff);
             }
             EvActDown(diff) => {
-                self.act_move_line_cursor(-*diff);
+                self.act_move_line_cursor_cyclic(-*diff);
             }
             EvActToggle => {
                 self.act_toggle();
@@ -392,19 +831,25 @@ impl EventHandler for Selection {
             }
             EvActHalfPageDown(diff) => {
                 let height = 1 - (self.height.load(Ordering::Relaxed) as i32);
-                self.act_move_line_cursor(height * *diff / 2);
+                self.act_move_line_cursor_cyclic(height * *diff / 2);
             }
             EvActHalfPageUp(diff) => {
                 let height = (self.height.load(Ordering::Relaxed) as i32) - 1;
-                self.act_move_line_cursor(height * *diff / 2);
+                self.act_move_line_cursor_cyclic(height * *diff / 2);
             }
             EvActPageDown(diff) => {
                 let height = 1 - (self.height.load(Ordering::Relaxed) as i32);
-                self.act_move_line_cursor(height * *diff);
+                self.act_move_line_cursor_cyclic(height * *diff);
             }
             EvActPageUp(diff) => {
                 let height = (self.height.load(Ordering::Relaxed) as i32) - 1;
-                self.act_move_line_cursor(height * *diff);
+                self.act_move_line_cursor_cyclic(height * *diff);
+            }
+            EvActJump if self.num_rows() > 0 => {
+                self.jump = Some(JumpMode::Jump);
+            }
+            EvActJumpAccept if self.num_rows() > 0 => {
+                self.jump = Some(JumpMode::JumpAccept);
             }
             EvActSelectRow(row) => {
                 self.act_select_screen_row(*row);
@@ -415,6 +860,9 @@ impl EventHandler for Selection {
             EvActScrollRight(diff) => {
                 self.act_scroll(*diff);
             }
+            EvActScrollTo(item_index) => {
+                self.act_scroll_to(*item_index);
+            }
             _ => return UpdateScreen::DONT_REDRAW,
         }
         UpdateScreen::REDRAW
@@ -422,11 +870,140 @@ impl EventHandler for Selection {
 }
 
 impl Selection {
+    // the shift and the full width to print `text` with, so that the match in it is visible
+    fn calc_shift(
+        &self,
+        text: &str,
+        (match_start, match_end): (usize, usize),
+        container_width: usize,
+    ) -> (usize, usize) {
+        let (shift, full_width) = reshape_string(
+            text,
+            container_width,
+            match_start,
+            match_end,
+            self.tabstop,
+            self.hscroll_off,
+        );
+
+        let shift = if self.no_hscroll {
+            0
+        } else if match_start == 0 && match_end == 0 {
+            // no match
+            if self.keep_right {
+                max(full_width, container_width) - container_width
+            } else {
+                self.calc_skip_width(text)
+            }
+        } else {
+            shift
+        };
+
+        (shift, full_width)
+    }
+
+    // print the lines of an item on `rows` rows from `row`, each line is shifted to show its own matches
+    #[allow(clippy::too_many_arguments)]
+    fn draw_lines(
+        &self,
+        canvas: &mut dyn Canvas,
+        row: usize,
+        rows: usize,
+        matched_item: &MatchedItem,
+        item_text: &str,
+        display_content: AnsiString,
+        container_width: usize,
+        default_attr: Attr,
+    ) {
+        let prefix_width = self.prefix_width();
+        let window = max(1, container_width.saturating_sub(2 * self.hscroll_off));
+
+        // the char indices of the matches in the whole text
+        let matched_indices: Vec<usize> = match matched_item.matched_range {
+            Some(ref range @ (MatchRange::Chars(_) | MatchRange::TermChars(_))) => {
+                range.char_indices().unwrap_or_default().to_vec()
+            }
+            Some(MatchRange::ByteRange(start, end)) => {
+                let start_char = item_text[..start].chars().count();
+                (start_char..start_char + item_text[start..end].chars().count()).collect()
+            }
+            None => vec![],
+        };
+        let same_text = display_content.stripped() == item_text;
+
+        let mut printers = vec![];
+        let mut line_start = 0;
+        for (line_no, line) in display_content.stripped().split('\n').take(rows).enumerate() {
+            let line_len = line.chars().count();
+            let (shift, full_width) = if same_text {
+                let line_indices: Vec<usize> = matched_indices
+                    .iter()
+                    .filter(|&&idx| idx >= line_start && idx < line_start + line_len)
+                    .map(|&idx| idx - line_start)
+                    .collect();
+                let line_match = densest_match_cluster(line, &line_indices, window, self.tabstop);
+                self.calc_shift(line, line_match, container_width)
+            } else {
+                (0, line.width_cjk())
+            };
+
+            printers.push(
+                LinePrinter::builder()
+                    .row(row + line_no)
+                    .col(prefix_width)
+                    .tabstop(self.tabstop)
+                    .container_width(container_width)
+                    .shift(shift)
+                    .text_width(full_width)
+                    .hscroll_offset(self.hscroll_offset)
+                    .build(),
+            );
+            line_start += line_len + 1;
+        }
+
+        let mut line_no = 0;
+        for (ch, attr) in display_content.iter() {
+            if ch == '\n' {
+                line_no += 1;
+                if line_no >= printers.len() {
+                    break;
+                }
+            } else {
+                printers[line_no].print_char(canvas, ch, default_attr.extend(attr), false);
+            }
+        }
+    }
+
+    // print the annotation at the right of the row, in at most half of the container, return the
+    // number of columns taken, with a space before it
+    fn draw_annotation(
+        &self,
+        canvas: &mut dyn Canvas,
+        row: usize,
+        container_width: usize,
+        annotation: &str,
+        default_attr: Attr,
+    ) -> usize {
+        let max_width = container_width / 2;
+        if max_width < 2 {
+            return 0;
+        }
+
+        let (screen_width, _) = canvas.size().unwrap_or((0, 0));
+        let annotation = truncate_to_width(annotation, max_width - 1);
+        let width = annotation.width_cjk();
+        let attr = default_attr.extend(self.theme.annotation());
+        let _ = canvas.print_with_attr(row, screen_width - width, &annotation, attr);
+        width + 1
+    }
+
     fn draw_item(
         &self,
         canvas: &mut dyn Canvas,
         row: usize,
+        rows: usize,
         matched_item: &MatchedItem,
+        tree_row: Option<&TreeRow>,
         is_current: bool,
     ) -> DrawResult<()> {
         let (screen_width, screen_height) = canvas.size()?;
@@ -434,7 +1011,8 @@ impl Selection {
         // update item heights
         self.height.store(screen_height, Ordering::Relaxed);
 
-        if screen_width < 3 {
+        let prefix_width = self.prefix_width();
+        if screen_width < prefix_width + 1 {
             return Err("screen width is too small".into());
         }
 
@@ -452,20 +1030,34 @@ impl Selection {
 
         // print selection cursor
         let index = (current_run_num(), matched_item.item_idx);
+        let marker_col = self.pointer_blank.len();
         if self.selected.contains_key(&index) {
-            let _ = canvas.print_with_attr(row, 1, ">", default_attr.extend(self.theme.selected()));
+            let _ = canvas.print_with_attr(
+                row,
+                marker_col,
+                &self.marker,
+                default_attr.extend(self.theme.selected()),
+            );
         } else {
-            let _ = canvas.print_with_attr(row, 1, " ", default_attr);
+            let _ = canvas.print_with_attr(row, marker_col, &self.unmarked, default_attr);
         }
 
         let item = &matched_item.upgrade_infallible();
         let item_text = item.text();
-        let container_width = screen_width - 2;
+        let container_width = screen_width - prefix_width;
+
+        // the annotation is right-aligned, the text is truncated to make room for it
+        let container_width = match item.annotation().filter(|annotation| !annotation.is_empty()) {
+            Some(annotation) => {
+                container_width - self.draw_annotation(canvas, row, container_width, &annotation, default_attr)
+            }
+            None => container_width,
+        };
 
         let opt_matches = match &matched_item.matched_range {
-            Some(MatchRange::Chars(ref matched_indices)) => Some(Matches::CharIndices(matched_indices)),
             Some(MatchRange::ByteRange(start, end)) => Some(Matches::ByteRange(*start, *end)),
-            _ => None,
+            Some(range) => range.char_indices().map(Matches::CharIndices),
+            None => None,
         };
 
         let context = DisplayContext {
@@ -474,52 +1066,74 @@ impl Selection {
             matches: opt_matches,
             container_width,
             highlight_attr: matched_attr,
+            matched_terms: matched_item.matched_range.as_ref().and_then(MatchRange::terms),
+            highlight_palette: &self.theme.term_highlights(matched_attr),
         };
 
         let display_content = item.display(context);
 
-        let mut printer = if display_content.stripped() == item_text {
+        if item_text.contains('\n') {
+            self.draw_lines(
+                canvas,
+                row,
+                rows,
+                matched_item,
+                &item_text,
+                display_content,
+                container_width,
+                default_attr,
+            );
+            return Ok(());
+        }
+
+        let same_text = display_content.stripped() == item_text;
+
+        // the position of the chars of the item in the tree or the padded columns
+        let (display_content, positions) = match (tree_row, self.tabular.as_ref()) {
+            (Some(tree_row), _) => {
+                let tree = self.tree.as_ref().expect("unreachable");
+                let (content, positions) = tree.layout(tree_row, &display_content);
+                (content, Some(positions))
+            }
+            (None, Some(tabular)) => {
+                let (content, positions) = tabular.layout(&display_content);
+                (content, Some(positions))
+            }
+            (None, None) => (display_content, None),
+        };
+        let position = |idx: usize| match positions.as_ref() {
+            Some(positions) => positions.get(idx).copied().flatten(),
+            None => Some(idx),
+        };
+
+        let mut printer = if same_text {
             // need to display the match content
+            let text = display_content.stripped();
             let (match_start_char, match_end_char) = match matched_item.matched_range {
-                Some(MatchRange::Chars(ref matched_indices)) => {
-                    if !matched_indices.is_empty() {
-                        (matched_indices[0], matched_indices[matched_indices.len() - 1] + 1)
-                    } else {
-                        (0, 0)
-                    }
+                Some(ref range @ (MatchRange::Chars(_) | MatchRange::TermChars(_))) => {
+                    let matched_indices = range.char_indices().unwrap_or_default();
+                    // scattered matches might not fit, show the densest part of them
+                    let window = max(1, container_width.saturating_sub(2 * self.hscroll_off));
+                    let matched_indices: Vec<usize> = matched_indices.iter().filter_map(|&idx| position(idx)).collect();
+                    densest_match_cluster(text, &matched_indices, window, self.tabstop)
                 }
                 Some(MatchRange::ByteRange(match_start, match_end)) => {
                     let match_start_char = item_text[..match_start].len();
                     let diff = item_text[match_start..match_end].len();
-                    (match_start_char, match_start_char + diff)
+                    let mut range = (match_start_char..match_start_char + diff).filter_map(position);
+                    match (positions.is_some(), range.next()) {
+                        (true, Some(start)) => (start, range.next_back().unwrap_or(start) + 1),
+                        _ => (match_start_char, match_start_char + diff),
+                    }
                 }
                 None => (0, 0),
             };
 
-            let (shift, full_width) = reshape_string(
-                &item_text,
-                container_width,
-                match_start_char,
-                match_end_char,
-                self.tabstop,
-            );
-
-            let shift = if self.no_hscroll {
-                0
-            } else if match_start_char == 0 && match_end_char == 0 {
-                // no match
-                if self.keep_right {
-                    max(full_width, container_width) - container_width
-                } else {
-                    self.calc_skip_width(&item_text)
-                }
-            } else {
-                shift
-            };
+            let (shift, full_width) = self.calc_shift(text, (match_start_char, match_end_char), container_width);
 
             LinePrinter::builder()
                 .row(row)
-                .col(2)
+                .col(prefix_width)
                 .tabstop(self.tabstop)
                 .container_width(container_width)
                 .shift(shift)
@@ -529,7 +1143,7 @@ impl Selection {
         } else {
             LinePrinter::builder()
                 .row(row)
-                .col(2)
+                .col(prefix_width)
                 .tabstop(self.tabstop)
                 .container_width(container_width)
                 .text_width(display_content.stripped().width_cjk())
@@ -546,45 +1160,145 @@ impl Selection {
 
 impl Draw for Selection {
     fn draw(&self, canvas: &mut dyn Canvas) -> DrawResult<()> {
-        let (_screen_width, screen_height) = canvas.size()?;
+        let (screen_width, screen_height) = canvas.size()?;
         canvas.clear()?;
 
+        match self.get_scrollbar(screen_height) {
+            Some(scrollbar) if screen_width > 1 => {
+                clear_canvas(canvas)?;
+                let glyph = self.scrollbar.unwrap_or(DEFAULT_SCROLLBAR);
+                scrollbar.draw(canvas, screen_width - 1, glyph, self.theme.scrollbar(), !self.reverse);
+                self.draw_items(&mut BoundedCanvas::new(0, 0, screen_width - 1, screen_height, canvas))
+            }
+            _ => self.draw_items(canvas),
+        }
+    }
+}
+
+impl Selection {
+    fn draw_items(&self, canvas: &mut dyn Canvas) -> DrawResult<()> {
+        let (_screen_width, screen_height) = canvas.size()?;
+
         let item_idx_lower = self.item_cursor;
         let max_upper = self.item_cursor + screen_height;
-        let item_idx_upper = min(max_upper, self.items.len());
+        let item_idx_upper = min(max_upper, self.num_rows());
 
         clear_canvas(canvas)?;
 
+        // the columns fit all the visible items, before any of them is laid out
+        if let (Some(tabular), None) = (self.tabular.as_ref(), self.tree.as_ref()) {
+            for item in (item_idx_lower..item_idx_upper).filter_map(|item_idx| self.row(item_idx)) {
+                let item = item.upgrade_infallible();
+                let text = item.text();
+                if !text.contains('\n') {
+                    tabular.observe(&text);
+                }
+            }
+        }
+
+        // items with multiple lines take more than one row
+        let mut used_rows = 0;
         for item_idx in item_idx_lower..item_idx_upper {
+            if used_rows >= screen_height {
+                break;
+            }
+
+            let rows = self
+                .row_height(item_idx)
+                .unwrap_or_else(|| panic!("model:draw_items: failed to get item at {}", item_idx));
+            let rows = min(rows, screen_height - used_rows);
+
             let line_cursor = item_idx - item_idx_lower;
             let line_no = if self.reverse {
                 // top down
-                line_cursor
+                used_rows
             } else {
-                // bottom up
-                screen_height - 1 - line_cursor
+                // bottom up, the lines of an item are still printed top down
+                screen_height - used_rows - rows
             };
+            used_rows += rows;
+
+            let item = match self.groups.as_ref().and_then(|groups| groups.get(item_idx)) {
+                Some(GroupRow::Header(group)) => {
+                    let attr = Attr {
+                        effect: Effect::BOLD,
+                        ..self.theme.header()
+                    };
+                    let _ = canvas.print_with_attr(line_no, 0, group, attr);
+                    continue;
+                }
+                _ => self.row(item_idx).expect("unreachable"),
+            };
+            let tree_row = self.tree.as_ref().and_then(|tree| tree.get(item_idx));
+
+            // print the cursor label, or the jump label over it
+            let jump_label = self.jump.and(self.jump_labels.get(line_cursor));
+            if let Some(jump_label) = jump_label {
+                let label = pad_to_width(&jump_label.to_string(), self.pointer_blank.width_cjk());
+                let attr = Attr {
+                    effect: Effect::BOLD | Effect::REVERSE,
+                    ..self.theme.cursor()
+                };
+                let _next_col = canvas.print_with_attr(line_no, 0, &label, attr).unwrap();
+            } else {
+                let label = if line_cursor == self.line_cursor {
+                    &self.pointer
+                } else {
+                    &self.pointer_blank
+                };
+                let _next_col = canvas.print_with_attr(line_no, 0, label, self.theme.cursor()).unwrap();
+            }
 
-            // print the cursor label
-            let label = if line_cursor == self.line_cursor { ">" } else { " " };
-            let _next_col = canvas.print_with_attr(line_no, 0, label, self.theme.cursor()).unwrap();
-
-            let item = self
-                .items
-                .get(item_idx)
-                .unwrap_or_else(|| panic!("model:draw_items: failed to get item at {}", item_idx));
-
-            let _ = self.draw_item(canvas, line_no, &item, line_cursor == self.line_cursor);
+            let _ = self.draw_item(canvas, line_no, rows, &item, tree_row, line_cursor == self.line_cursor);
         }
 
         Ok(())
     }
 }
 
+// pad `text` with spaces to the display `width`
+fn pad_to_width(text: &str, width: usize) -> String {
+    let padding = max(width, text.width_cjk()) - text.width_cjk();
+    format!("{}{}", text, " ".repeat(padding))
+}
+
+// truncate `text` to the display `width`, ending with an ellipsis if anything is cut
+fn truncate_to_width(text: &str, width: usize) -> String {
+    if text.width_cjk() <= width {
+        return text.to_string();
+    }
+
+    let mut ret = String::new();
+    let mut text_width = 0;
+    for ch in text.chars() {
+        let ch_width = ch.width_cjk().unwrap_or(0);
+        if text_width + ch_width + 1 > width {
+            break;
+        }
+        ret.push(ch);
+        text_width += ch_width;
+    }
+    ret.push('…');
+    ret
+}
+
 impl Widget<Event> for Selection {
-    fn on_event(&self, event: TermEvent, _rect: Rectangle) -> Vec<Event> {
+    fn on_event(&self, event: TermEvent, rect: Rectangle) -> Vec<Event> {
         let mut ret = vec![];
+        let scrollbar = self.get_scrollbar(rect.height);
         match event {
+            TermEvent::Key(Key::SingleClick(MouseButton::Left, row, col))
+                if scrollbar.is_some() && col as usize + 1 == rect.width =>
+            {
+                let row = row as usize;
+                let row = if self.reverse {
+                    row
+                } else {
+                    rect.height - 1 - min(row, rect.height - 1)
+                };
+                let offset = scrollbar.map(|scrollbar| scrollbar.offset_at(row)).unwrap_or(0);
+                ret.push(Event::EvActScrollTo(offset))
+            }
             TermEvent::Key(Key::WheelUp(.., count)) => ret.push(Event::EvActUp(count as i32)),
             TermEvent::Key(Key::WheelDown(.., count)) => ret.push(Event::EvActDown(count as i32)),
             TermEvent::Key(Key::SingleClick(MouseButton::Left, row, _)) => {
@@ -600,3 +1314,329 @@ impl Widget<Event> for Selection {
         ret
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::borrow::Cow;
+    use tuikit::screen::Screen;
+
+    // the matched items of `items`, ranked in their order, indexed from `start`
+    fn matched(items: &[Arc<dyn SkimItem>], start: usize) -> Vec<MatchedItem> {
+        items
+            .iter()
+            .enumerate()
+            .map(|(idx, item)| MatchedItem {
+                item: Arc::downgrade(item),
+                rank: Default::default(),
+                matched_range: None,
+                item_idx: (start + idx) as u32,
+            })
+            .collect()
+    }
+
+    struct GroupedItem(&'static str, &'static str);
+
+    impl SkimItem for GroupedItem {
+        fn text(&self) -> Cow<str> {
+            Cow::Borrowed(self.0)
+        }
+
+        fn group(&self) -> Option<Cow<str>> {
+            Some(Cow::Borrowed(self.1))
+        }
+    }
+
+    fn current(selection: &Selection) -> String {
+        selection
+            .get_current_item()
+            .map(|item| item.text().to_string())
+            .unwrap_or_default()
+    }
+
+    // the lines drawn on a screen of the size, trailing spaces trimmed
+    fn draw(selection: &Selection, width: usize, height: usize) -> Vec<String> {
+        let mut screen = Screen::new(width, height);
+        selection.draw(&mut screen).unwrap();
+        let mut lines = vec![String::new(); height];
+        for (row, _, cell) in screen.iter_cell() {
+            lines[row].push(if cell.ch == '\0' { ' ' } else { cell.ch });
+        }
+        lines.iter().map(|line| line.trim_end().to_string()).collect()
+    }
+
+    #[test]
+    fn test_cycle() {
+        let items: Vec<Arc<dyn SkimItem>> = vec![Arc::new("a"), Arc::new("b"), Arc::new("c")];
+        for layout in ["default", "reverse"] {
+            let options = SkimOptions {
+                cycle: true,
+                layout,
+                ..Default::default()
+            };
+            let mut selection = Selection::with_options(&options);
+            selection.height.store(10, Ordering::Relaxed);
+            selection.append_sorted_items(matched(&items, 0));
+
+            // the first item is at the bottom of the default layout, at the top of the reverse one
+            let (towards_last, towards_first) = if layout == "reverse" {
+                (Event::EvActDown(1), Event::EvActUp(1))
+            } else {
+                (Event::EvActUp(1), Event::EvActDown(1))
+            };
+
+            assert_eq!(current(&selection), "a");
+            selection.handle(&towards_first);
+            assert_eq!(current(&selection), "c", "{}: wrap to the last item", layout);
+            selection.handle(&towards_last);
+            assert_eq!(current(&selection), "a", "{}: wrap to the first item", layout);
+            selection.handle(&towards_last);
+            assert_eq!(current(&selection), "b", "{}: move without wrapping", layout);
+        }
+    }
+
+    #[test]
+    fn test_jump() {
+        let items: Vec<Arc<dyn SkimItem>> = vec![Arc::new("a"), Arc::new("b"), Arc::new("c")];
+        let options = SkimOptions {
+            multi: true,
+            jump_labels: Some("xyzw"),
+            ..Default::default()
+        };
+        let mut selection = Selection::with_options(&options);
+        selection.height.store(10, Ordering::Relaxed);
+        selection.append_sorted_items(matched(&items, 0));
+
+        // `jump` moves to the labeled row and toggles it
+        selection.handle(&Event::EvActJump);
+        assert_eq!(selection.jump_mode(), Some(JumpMode::Jump));
+        assert_eq!(selection.act_jump_key(Key::Char('z')), Event::EvActIgnore);
+        assert_eq!(selection.jump_mode(), None);
+        assert_eq!(current(&selection), "c");
+        assert_eq!(selection.get_num_selected(), 1);
+
+        // any other key cancels it, keeping the cursor
+        selection.handle(&Event::EvActJump);
+        assert_eq!(selection.act_jump_key(Key::ESC), Event::EvActIgnore);
+        assert_eq!(selection.jump_mode(), None);
+        assert_eq!(current(&selection), "c");
+
+        // so does a label beyond the items
+        selection.handle(&Event::EvActJumpAccept);
+        assert_eq!(selection.act_jump_key(Key::Char('w')), Event::EvActIgnore);
+        assert_eq!(current(&selection), "c");
+
+        // `jump-accept` accepts the labeled row
+        selection.handle(&Event::EvActJumpAccept);
+        assert_eq!(selection.act_jump_key(Key::Char('y')), Event::EvActAccept(None));
+        assert_eq!(current(&selection), "b");
+        assert_eq!(selection.get_num_selected(), 1);
+    }
+
+    #[test]
+    fn test_cycle_with_group_header() {
+        let items: Vec<Arc<dyn SkimItem>> = vec![
+            Arc::new(GroupedItem("a.rs", "files")),
+            Arc::new(GroupedItem("b.rs", "files")),
+        ];
+        let options = SkimOptions {
+            groups: Some("rank"),
+            cycle: true,
+            ..Default::default()
+        };
+        let mut selection = Selection::with_options(&options);
+        selection.height.store(10, Ordering::Relaxed);
+        selection.append_sorted_items(matched(&items, 0));
+        selection.sync_rows();
+
+        // the rows are: [files], a.rs, b.rs
+        assert_eq!(current(&selection), "a.rs");
+        selection.handle(&Event::EvActDown(1));
+        assert_eq!(current(&selection), "b.rs");
+        selection.handle(&Event::EvActUp(1));
+        assert_eq!(current(&selection), "a.rs");
+    }
+
+    #[test]
+    fn test_multi_line_cursor() {
+        let items: Vec<Arc<dyn SkimItem>> = vec![Arc::new("a\nb\nc"), Arc::new("d"), Arc::new("e\nf")];
+        let options = SkimOptions {
+            multi_line: Some("0"),
+            layout: "reverse",
+            ..Default::default()
+        };
+        let mut selection = Selection::with_options(&options);
+        selection.height.store(4, Ordering::Relaxed);
+        selection.append_sorted_items(matched(&items, 0));
+        assert_eq!(draw(&selection, 10, 4), vec!["> a", "  b", "  c", "  d"]);
+
+        // the cursor moves by items, not by rows
+        selection.handle(&Event::EvActDown(1));
+        assert_eq!(current(&selection), "d");
+        assert_eq!(draw(&selection, 10, 4), vec!["  a", "  b", "  c", "> d"]);
+
+        // and scrolls until the whole item fits
+        selection.handle(&Event::EvActDown(1));
+        assert_eq!(current(&selection), "e\nf");
+        assert_eq!(draw(&selection, 10, 4), vec!["  d", "> e", "  f", ""]);
+
+        selection.handle(&Event::EvActUp(2));
+        assert_eq!(current(&selection), "a\nb\nc");
+    }
+
+    #[test]
+    fn test_multi_line_max_lines() {
+        let items: Vec<Arc<dyn SkimItem>> = vec![Arc::new("a\nb\nc"), Arc::new("d")];
+        let options = SkimOptions {
+            multi_line: Some("2"),
+            ..Default::default()
+        };
+        let mut selection = Selection::with_options(&options);
+        selection.append_sorted_items(matched(&items, 0));
+
+        // at most 2 rows for an item, the lines are still printed top down
+        assert_eq!(draw(&selection, 10, 4), vec!["", "  d", "> a", "  b"]);
+        selection.handle(&Event::EvActUp(1));
+        assert_eq!(current(&selection), "d");
+    }
+
+    #[test]
+    fn test_multi_line_highlight() {
+        let items: Vec<Arc<dyn SkimItem>> = vec![Arc::new("ab\ncd")];
+        let mut matched_items = matched(&items, 0);
+        // "a" and "d", the latter on the second line
+        matched_items[0].matched_range = Some(MatchRange::Chars(vec![0, 4].into()));
+        let options = SkimOptions {
+            multi_line: Some("0"),
+            layout: "reverse",
+            ..Default::default()
+        };
+        let mut selection = Selection::with_options(&options);
+        selection.append_sorted_items(matched_items);
+
+        let mut screen = Screen::new(10, 3);
+        selection.draw(&mut screen).unwrap();
+        let attr_of = |ch: char| {
+            screen
+                .iter_cell()
+                .find(|(_, _, cell)| cell.ch == ch)
+                .map(|(_, _, cell)| cell.attr)
+                .unwrap()
+        };
+        assert_ne!(attr_of('a'), attr_of('b'));
+        assert_eq!(attr_of('d'), attr_of('a'));
+        assert_eq!(attr_of('c'), attr_of('b'));
+    }
+
+    #[test]
+    fn test_draw_group_headers() {
+        let items: Vec<Arc<dyn SkimItem>> = vec![
+            Arc::new(GroupedItem("a.rs", "files")),
+            Arc::new(GroupedItem("b.rs", "files")),
+        ];
+        for layout in ["default", "reverse"] {
+            let options = SkimOptions {
+                groups: Some("rank"),
+                layout,
+                ..Default::default()
+            };
+            let mut selection = Selection::with_options(&options);
+            selection.append_sorted_items(matched(&items, 0));
+            selection.sync_rows();
+
+            // the header is above its items, the best one is next to the query
+            let expected = if layout == "reverse" {
+                vec!["files", "> a.rs", "  b.rs", ""]
+            } else {
+                vec!["", "files", "  b.rs", "> a.rs"]
+            };
+            assert_eq!(draw(&selection, 10, 4), expected, "{}", layout);
+        }
+    }
+
+    #[test]
+    fn test_draw_tabular() {
+        let items: Vec<Arc<dyn SkimItem>> = vec![Arc::new("a b c"), Arc::new("aaaa bb c")];
+        let options = SkimOptions {
+            tabular: Some("0"),
+            ..Default::default()
+        };
+        let mut selection = Selection::with_options(&options);
+        selection.append_sorted_items(matched(&items, 0));
+
+        // the first row drawn is aligned with the wider one drawn after it
+        assert_eq!(draw(&selection, 20, 3), vec!["", "  aaaa bb c", "> a    b  c"]);
+
+        // the widths start over with the new results
+        selection.clear();
+        selection.append_sorted_items(matched(&items[..1], 0));
+        assert_eq!(draw(&selection, 20, 3), vec!["", "", "> a b c"]);
+    }
+
+    #[test]
+    fn test_tree_built_lazily() {
+        let items: Vec<Arc<dyn SkimItem>> = vec![Arc::new("src/a.rs"), Arc::new("src/b.rs")];
+        let options = SkimOptions {
+            tree: Some("/"),
+            ..Default::default()
+        };
+        let mut selection = Selection::with_options(&options);
+        selection.height.store(10, Ordering::Relaxed);
+
+        // neither the batches nor the heartbeats right after them build the tree
+        selection.append_sorted_items(matched(&items[..1], 0));
+        selection.append_sorted_items(matched(&items[1..], 1));
+        selection.handle(&Event::EvHeartBeat);
+        assert_eq!(selection.num_rows(), 0);
+
+        // a heartbeat does after a while, moving the cursor right away
+        selection.rows_built_at -= ROWS_REBUILD_INTERVAL;
+        selection.handle(&Event::EvHeartBeat);
+        assert_eq!(selection.num_rows(), 1);
+
+        selection.append_sorted_items(matched(&items, 0));
+        selection.handle(&Event::EvActDown(1));
+        assert_eq!(selection.num_rows(), 1);
+        assert!(!selection.rows_dirty);
+    }
+
+    #[test]
+    fn test_pad_to_width() {
+        assert_eq!(pad_to_width("", 2), "  ");
+        assert_eq!(pad_to_width("a", 3), "a  ");
+        assert_eq!(pad_to_width("中", 3), "中 ");
+        assert_eq!(pad_to_width("中文", 1), "中文");
+    }
+
+    #[test]
+    fn test_set_glyphs() {
+        let mut selection = Selection::new();
+
+        // the marker and the unmarked glyph are padded to the same width
+        selection.set_glyphs("中", "✓", "");
+        assert_eq!(selection.pointer_blank, "  ");
+        assert_eq!(selection.marker, "✓");
+        assert_eq!(selection.unmarked, " ");
+        assert_eq!(selection.prefix_width(), 3);
+
+        selection.set_glyphs(">", "", "文");
+        assert_eq!(selection.pointer_blank, " ");
+        assert_eq!(selection.marker, "  ");
+        assert_eq!(selection.unmarked, "文");
+        assert_eq!(selection.prefix_width(), 3);
+
+        // no glyphs, no prefix
+        selection.set_glyphs("", "", "");
+        assert_eq!(selection.pointer_blank, "");
+        assert_eq!(selection.marker, "");
+        assert_eq!(selection.unmarked, "");
+        assert_eq!(selection.prefix_width(), 0);
+    }
+
+    #[test]
+    fn test_truncate_to_width() {
+        assert_eq!(truncate_to_width("abc", 3), "abc");
+        assert_eq!(truncate_to_width("abcdef", 4), "abc…");
+        assert_eq!(truncate_to_width("中文字", 4), "中…");
+    }
+}
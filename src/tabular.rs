@@ -0,0 +1,184 @@
+//! Tabular display of delimited items (`--tabular`)
+//!
+//! The fields of an item (split by `--delimiter`) are padded to the widths of the columns, which
+//! are the widest fields seen since the matched items last changed. A cell is a field with its delimiter, trailing whitespaces
+//! trimmed, so that `ps` like output is aligned on the words while `a,b` becomes `a, b`.
+use std::cell::RefCell;
+use std::cmp::{max, min};
+
+use regex::Regex;
+use tuikit::prelude::*;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::ansi::AnsiString;
+use crate::field::get_ranges_by_delimiter;
+
+pub const DEFAULT_TABULAR_MAX_WIDTH: usize = 40;
+const ELLIPSIS: char = '…';
+
+pub struct Tabular {
+    delimiter: Regex,
+    // the maximum width of a column (except the last one), 0 for no limit
+    max_width: usize,
+    // the widths of the columns, grow with the items seen until reset
+    widths: RefCell<Vec<usize>>,
+}
+
+impl Tabular {
+    pub fn new(delimiter: Regex, max_width: usize) -> Self {
+        Self {
+            delimiter,
+            max_width,
+            widths: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// the cells of `text`, i.e. the fields with their delimiters
+    fn cells<'a>(&self, text: &'a str) -> Vec<&'a str> {
+        let ranges = get_ranges_by_delimiter(&self.delimiter, text);
+        let num_cells = ranges.len();
+        ranges
+            .iter()
+            .enumerate()
+            .map(|(idx, &(start, _))| {
+                if idx + 1 < num_cells {
+                    text[start..ranges[idx + 1].0].trim_end()
+                } else {
+                    &text[start..]
+                }
+            })
+            .collect()
+    }
+
+    fn column_width(&self, width: usize) -> usize {
+        if self.max_width > 0 {
+            min(width, self.max_width)
+        } else {
+            width
+        }
+    }
+
+    /// forget the widths of the columns, e.g. the wide items are no longer matched
+    pub fn reset(&self) {
+        self.widths.borrow_mut().clear();
+    }
+
+    /// update the widths of the columns with the cells of `text`
+    pub fn observe(&self, text: &str) {
+        let cells = self.cells(text);
+        let mut widths = self.widths.borrow_mut();
+        // the last cell is not padded, it takes no part in the widths
+        for (idx, cell) in cells.iter().take(cells.len() - 1).enumerate() {
+            let width = self.column_width(cell.width_cjk());
+            match widths.get_mut(idx) {
+                Some(w) => *w = max(*w, width),
+                None => widths.push(width),
+            }
+        }
+    }
+
+    /// pad the cells of `content` to the widths of the columns, return the padded content and the
+    /// position of every char of `content` in it (`None` for the chars removed)
+    pub fn layout(&self, content: &AnsiString) -> (AnsiString, Vec<Option<usize>>) {
+        let text = content.stripped();
+        // the rows are observed before drawing, this covers the items displayed otherwise than their text
+        self.observe(text);
+
+        let widths = self.widths.borrow();
+        let chars: Vec<(char, Attr)> = content.iter().collect();
+        let cells = self.cells(text);
+        let num_cells = cells.len();
+
+        let mut output = Vec::with_capacity(chars.len());
+        let mut positions = vec![None; chars.len()];
+        let mut char_idx = 0;
+        let mut cell_start = 0;
+
+        for (idx, cell) in cells.iter().enumerate() {
+            // the chars between the cells, i.e. the trimmed whitespaces of the delimiter
+            let cell_offset = text[cell_start..].find(cell).unwrap_or(0) + cell_start;
+            char_idx += text[cell_start..cell_offset].chars().count();
+            cell_start = cell_offset + cell.len();
+
+            let is_last = idx + 1 == num_cells;
+            let column_width = widths.get(idx).copied().unwrap_or(0);
+            let truncate = !is_last && cell.width_cjk() > column_width;
+
+            let mut width = 0;
+            for (ch_idx, ch) in (char_idx..).zip(cell.chars()) {
+                let ch_width = ch.width_cjk().unwrap_or(0);
+                if truncate && width + ch_width + 1 > column_width {
+                    output.push((ELLIPSIS, Attr::default()));
+                    width += 1;
+                    break;
+                }
+
+                positions[ch_idx] = Some(output.len());
+                output.push(chars[ch_idx]);
+                width += ch_width;
+            }
+            char_idx += cell.chars().count();
+
+            if !is_last {
+                // one space between the columns
+                let padding = column_width.saturating_sub(width) + 1;
+                output.resize(output.len() + padding, (' ', Attr::default()));
+            }
+        }
+
+        (to_ansi_string(output), positions)
+    }
+}
+
+fn to_ansi_string(chars: Vec<(char, Attr)>) -> AnsiString {
+    let mut fragments: Vec<(Attr, (u32, u32))> = Vec::new();
+    for (idx, &(_, attr)) in chars.iter().enumerate() {
+        if attr == Attr::default() {
+            continue;
+        }
+
+        let idx = idx as u32;
+        match fragments.last_mut() {
+            Some((last_attr, (_, end))) if *last_attr == attr && *end == idx => *end = idx + 1,
+            _ => fragments.push((attr, (idx, idx + 1))),
+        }
+    }
+
+    AnsiString::new_string(chars.into_iter().map(|(ch, _)| ch).collect(), fragments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layout() {
+        let tabular = Tabular::new(Regex::new(r"[\t ]+").unwrap(), 6);
+        tabular.observe("a bbb c");
+        tabular.observe("aa b c");
+
+        let (content, positions) = tabular.layout(&AnsiString::from("a b c"));
+        assert_eq!(content.stripped(), "a  b   c");
+        assert_eq!(positions, vec![Some(0), None, Some(3), None, Some(7)]);
+
+        let (content, _) = tabular.layout(&AnsiString::from("a,b c"));
+        assert_eq!(content.stripped(), "a,b c");
+
+        // columns wider than the maximum are truncated, the last column is never
+        let (content, positions) = tabular.layout(&AnsiString::from("abcdefgh x yyyyyyyy"));
+        assert_eq!(content.stripped(), "abcde… x   yyyyyyyy");
+        assert_eq!(&positions[4..7], &[Some(4), None, None]);
+    }
+
+    #[test]
+    fn test_layout_delimiter() {
+        let tabular = Tabular::new(Regex::new(",").unwrap(), 0);
+        tabular.observe("aaaa,b");
+        let (content, _) = tabular.layout(&AnsiString::from("a,b"));
+        assert_eq!(content.stripped(), "a,    b");
+
+        tabular.reset();
+        let (content, _) = tabular.layout(&AnsiString::from("a,b"));
+        assert_eq!(content.stripped(), "a, b");
+    }
+}
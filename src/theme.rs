@@ -1,11 +1,23 @@
 ///! Handle the color theme
 use crate::options::SkimOptions;
-use std::sync::LazyLock;
+use std::io;
+use std::path::Path;
+use std::sync::{LazyLock, OnceLock};
+use std::time::Duration;
 use tuikit::prelude::*;
 
 #[rustfmt::skip]
 pub static DEFAULT_THEME:  LazyLock<ColorTheme> = LazyLock::new(|| ColorTheme::dark256());
 
+// the number of colors for the query terms after the first one
+const NUM_TERM_COLORS: usize = 5;
+
+// the time to wait for the terminal to answer the query of its background color
+const BACKGROUND_QUERY_TIMEOUT: Duration = Duration::from_millis(200);
+
+// the background color of the terminal, asked at most once
+static TERMINAL_BACKGROUND: OnceLock<Option<(u8, u8, u8)>> = OnceLock::new();
+
 /// The color scheme of skim's UI
 ///
 /// <pre>
@@ -36,12 +48,32 @@ pub struct ColorTheme {
     query_bg:             Color,
     query_effect:         Effect,
     spinner:              Color,
+    spinner_effect:       Effect,
     info:                 Color,
+    info_effect:          Effect,
     prompt:               Color,
+    prompt_effect:        Effect,
     cursor:               Color,
+    cursor_effect:        Effect,
     selected:             Color,
+    selected_effect:      Effect,
     header:               Color,
+    header_effect:        Effect,
     border:               Color,
+    border_effect:        Effect,
+    label:                Color,
+    label_effect:         Effect,
+    scrollbar:            Color,
+    scrollbar_effect:     Effect,
+    preview_scrollbar:    Color,
+    preview_scrollbar_effect: Effect,
+    annotation:           Color,
+    annotation_effect:    Effect,
+    ghost:                Color,
+    ghost_effect:         Effect,
+    // the highlights of the query terms after the first one
+    matched_terms:        [Color; NUM_TERM_COLORS],
+    matched_terms_effect: [Effect; NUM_TERM_COLORS],
 }
 
 #[rustfmt::skip]
@@ -49,10 +81,84 @@ pub struct ColorTheme {
 impl ColorTheme {
     pub fn init_from_options(options: &SkimOptions) -> ColorTheme {
         // register
-        if let Some(color) = options.color {
-            ColorTheme::from_options(color)
+        let mut theme = if let Some(color) = options.color {
+            ColorTheme::parse(color).unwrap_or_else(|err| {
+                warn!("invalid color spec: {}", err);
+                ColorTheme::dark256()
+            })
         } else {
             ColorTheme::dark256()
+        };
+        if options.no_bold {
+            theme.remove_effect(Effect::BOLD);
+        }
+        theme
+    }
+
+    /// Remove the effect from all the elements, e.g. bold for `--no-bold`
+    fn remove_effect(&mut self, effect: Effect) {
+        for elem_effect in &mut [
+            &mut self.normal_effect,
+            &mut self.matched_effect,
+            &mut self.current_effect,
+            &mut self.current_match_effect,
+            &mut self.query_effect,
+            &mut self.spinner_effect,
+            &mut self.info_effect,
+            &mut self.prompt_effect,
+            &mut self.cursor_effect,
+            &mut self.selected_effect,
+            &mut self.header_effect,
+            &mut self.border_effect,
+            &mut self.label_effect,
+            &mut self.scrollbar_effect,
+            &mut self.preview_scrollbar_effect,
+            &mut self.annotation_effect,
+            &mut self.ghost_effect,
+        ] {
+            elem_effect.remove(effect);
+        }
+        for term_effect in self.matched_terms_effect.iter_mut() {
+            term_effect.remove(effect);
+        }
+    }
+
+    /// Read a theme file: the color specs of its lines are joined, the lines starting with `#` are
+    /// comments, e.g.
+    ///
+    /// ```text
+    /// # solarized light
+    /// light
+    /// fg:#657b83,bg:#fdf6e3
+    /// hl:#268bd2:bold
+    /// ```
+    pub fn read_spec(path: &Path) -> io::Result<String> {
+        let content = std::fs::read_to_string(path)?;
+        let specs: Vec<&str> = content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .collect();
+        Ok(specs.join(","))
+    }
+
+    /// Ask the terminal for its background color if the spec has the `auto` scheme. It has to be
+    /// done before the terminal is taken over, otherwise the answer is read as input.
+    pub fn detect_background(spec: Option<&str>) {
+        if let Some(spec) = spec {
+            if spec.split(',').any(|pair| pair == "auto") {
+                TERMINAL_BACKGROUND.get_or_init(query_background);
+            }
+        }
+    }
+
+    /// `light` on a light background, `dark` otherwise (or if the terminal doesn't answer). The
+    /// terminal is only asked by `detect_background`, so that parsing the spec doesn't touch it,
+    /// e.g. to validate it in the filter mode.
+    fn auto() -> Self {
+        match TERMINAL_BACKGROUND.get().copied().flatten() {
+            Some((r, g, b)) if is_light(r, g, b) => ColorTheme::light256(),
+            _ => ColorTheme::dark256(),
         }
     }
 
@@ -74,12 +180,31 @@ impl ColorTheme {
             query_bg:             Color::Default,
             query_effect:         Effect::empty(),
             spinner:              Color::Default,
+            spinner_effect:       Effect::BOLD,
             info:                 Color::Default,
+            info_effect:          Effect::empty(),
             prompt:               Color::Default,
+            prompt_effect:        Effect::empty(),
             cursor:               Color::Default,
+            cursor_effect:        Effect::empty(),
             selected:             Color::Default,
+            selected_effect:      Effect::empty(),
             header:               Color::Default,
+            header_effect:        Effect::empty(),
             border:               Color::Default,
+            border_effect:        Effect::empty(),
+            label:                Color::Default,
+            label_effect:         Effect::empty(),
+            scrollbar:            Color::Default,
+            scrollbar_effect:     Effect::empty(),
+            preview_scrollbar:    Color::Default,
+            preview_scrollbar_effect: Effect::empty(),
+            annotation:           Color::Default,
+            annotation_effect:    Effect::empty(),
+            ghost:                Color::Default,
+            ghost_effect:         Effect::empty(),
+            matched_terms:        [Color::Default; NUM_TERM_COLORS],
+            matched_terms_effect: [Effect::empty(); NUM_TERM_COLORS],
         }
     }
 
@@ -88,6 +213,7 @@ impl ColorTheme {
             matched_effect:       Effect::UNDERLINE,
             current_effect:       Effect::REVERSE,
             current_match_effect: Effect::UNDERLINE | Effect::REVERSE,
+            ghost_effect:         Effect::DIM,
             ..ColorTheme::empty()
         }
     }
@@ -107,6 +233,12 @@ impl ColorTheme {
             selected:         Color::MAGENTA,
             header:           Color::CYAN,
             border:           Color::LIGHT_BLACK,
+            label:            Color::CYAN,
+            scrollbar:        Color::LIGHT_BLACK,
+            preview_scrollbar: Color::LIGHT_BLACK,
+            annotation:       Color::LIGHT_BLACK,
+            ghost:            Color::LIGHT_BLACK,
+            matched_terms:    [Color::YELLOW, Color::BLUE, Color::MAGENTA, Color::CYAN, Color::RED],
             ..ColorTheme::empty()
         }
     }
@@ -126,6 +258,12 @@ impl ColorTheme {
             selected:         Color::AnsiValue(168),
             header:           Color::AnsiValue(109),
             border:           Color::AnsiValue(59),
+            label:            Color::AnsiValue(109),
+            scrollbar:        Color::AnsiValue(59),
+            preview_scrollbar: Color::AnsiValue(59),
+            annotation:       Color::AnsiValue(244),
+            ghost:            Color::AnsiValue(244),
+            matched_terms:    [Color::AnsiValue(173), Color::AnsiValue(110), Color::AnsiValue(176), Color::AnsiValue(186), Color::AnsiValue(140)],
             ..ColorTheme::empty()
         }
     }
@@ -145,6 +283,12 @@ impl ColorTheme {
             selected:         Color::AnsiValue(168),
             header:           Color::AnsiValue(109),
             border:           Color::AnsiValue(59),
+            label:            Color::AnsiValue(109),
+            scrollbar:        Color::AnsiValue(59),
+            preview_scrollbar: Color::AnsiValue(59),
+            annotation:       Color::AnsiValue(244),
+            ghost:            Color::AnsiValue(244),
+            matched_terms:    [Color::AnsiValue(208), Color::AnsiValue(81), Color::AnsiValue(141), Color::AnsiValue(197), Color::AnsiValue(148)],
             ..ColorTheme::empty()
         }
     }
@@ -164,61 +308,97 @@ impl ColorTheme {
             selected:         Color::AnsiValue(168),
             header:           Color::AnsiValue(31),
             border:           Color::AnsiValue(145),
+            label:            Color::AnsiValue(31),
+            scrollbar:        Color::AnsiValue(145),
+            preview_scrollbar: Color::AnsiValue(145),
+            annotation:       Color::AnsiValue(244),
+            ghost:            Color::AnsiValue(244),
+            matched_terms:    [Color::AnsiValue(166), Color::AnsiValue(25), Color::AnsiValue(127), Color::AnsiValue(94), Color::AnsiValue(54)],
             ..ColorTheme::empty()
         }
     }
 
-    #[allow(clippy::wildcard_in_or_patterns)]
-    fn from_options(color: &str) -> Self {
+    /// Parse a color spec, e.g. `light,hl:#ff0000:bold:underline,bg+:-1`: a base scheme, then
+    /// the elements with their colors and effects. The effects given replace the ones of the
+    /// element, `regular` clears them.
+    pub fn parse(spec: &str) -> std::result::Result<Self, String> {
         let mut theme = ColorTheme::dark256();
-        for pair in color.split(',') {
-            let color: Vec<&str> = pair.split(':').collect();
-            if color.len() < 2 {
-                theme = match color[0] {
-                    "molokai"  => ColorTheme::molokai256(),
-                    "light"    => ColorTheme::light256(),
-                    "16"       => ColorTheme::default16(),
-                    "bw"       => ColorTheme::bw(),
-                    "empty"    => ColorTheme::empty(),
-                    "dark" | "default" | _ => ColorTheme::dark256(),
+        for pair in spec.split(',').filter(|pair| !pair.is_empty()) {
+            let mut parts = pair.split(':');
+            let name = parts.next().unwrap_or("");
+            let specs: Vec<&str> = parts.filter(|part| !part.is_empty()).collect();
+            if !pair.contains(':') {
+                theme = match name {
+                    "molokai"           => ColorTheme::molokai256(),
+                    "light"             => ColorTheme::light256(),
+                    "16"                => ColorTheme::default16(),
+                    "bw"                => ColorTheme::bw(),
+                    "empty"             => ColorTheme::empty(),
+                    "auto"              => ColorTheme::auto(),
+                    "dark" | "default"  => ColorTheme::dark256(),
+                    _ => return Err(format!("unknown color scheme '{}'", name)),
                 };
                 continue;
             }
 
-            let new_color = if color[1].len() == 7 {
-                // 256 color
-                let r = u8::from_str_radix(&color[1][1..3], 16).unwrap_or(255);
-                let g = u8::from_str_radix(&color[1][3..5], 16).unwrap_or(255);
-                let b = u8::from_str_radix(&color[1][5..7], 16).unwrap_or(255);
-                Color::Rgb(r, g, b)
-            } else {
-                color[1].parse::<u8>()
-                    .map(Color::AnsiValue)
-                    .unwrap_or(Color::Default)
-            };
+            let (color, effect) = theme
+                .element(name)
+                .ok_or_else(|| format!("unknown color name '{}'", name))?;
+
+            let mut new_effect: Option<Effect> = None;
+            for spec in specs {
+                if spec == "italic" {
+                    return Err(format!("attribute 'italic' for '{}' is not supported by the terminal", name));
+                } else if let Some(e) = parse_effect(spec) {
+                    new_effect = Some(new_effect.unwrap_or_else(Effect::empty) | e);
+                } else if let Some(c) = parse_color(spec) {
+                    *color = c;
+                } else {
+                    return Err(format!("invalid color or attribute '{}' for '{}'", spec, name));
+                }
+            }
 
-            match color[0] {
-                "fg"                    => theme.fg               = new_color,
-                "bg"                    => theme.bg               = new_color,
-                "matched" | "hl"        => theme.matched          = new_color,
-                "matched_bg"            => theme.matched_bg       = new_color,
-                "current" | "fg+"       => theme.current          = new_color,
-                "current_bg" | "bg+"    => theme.current_bg       = new_color,
-                "current_match" | "hl+" => theme.current_match    = new_color,
-                "current_match_bg"      => theme.current_match_bg = new_color,
-                "query"                 => theme.query_fg         = new_color,
-                "query_bg"              => theme.query_bg         = new_color,
-                "spinner"               => theme.spinner          = new_color,
-                "info"                  => theme.info             = new_color,
-                "prompt"                => theme.prompt           = new_color,
-                "cursor" | "pointer"    => theme.cursor           = new_color,
-                "selected" | "marker"   => theme.selected         = new_color,
-                "header"                => theme.header           = new_color,
-                "border"                => theme.border           = new_color,
-                _ => {}
+            if let Some(new_effect) = new_effect {
+                *effect = new_effect;
             }
         }
-        theme
+        Ok(theme)
+    }
+
+    /// the color and the effect set by the color name, the background shares the effect of the
+    /// foreground
+    fn element(&mut self, name: &str) -> Option<(&mut Color, &mut Effect)> {
+        let element = match name {
+            "fg"                    => (&mut self.fg,                &mut self.normal_effect),
+            "bg"                    => (&mut self.bg,                &mut self.normal_effect),
+            "matched" | "hl"        => (&mut self.matched,           &mut self.matched_effect),
+            "matched_bg"            => (&mut self.matched_bg,        &mut self.matched_effect),
+            "current" | "fg+"       => (&mut self.current,           &mut self.current_effect),
+            "current_bg" | "bg+"    => (&mut self.current_bg,        &mut self.current_effect),
+            "current_match" | "hl+" => (&mut self.current_match,     &mut self.current_match_effect),
+            "current_match_bg"      => (&mut self.current_match_bg,  &mut self.current_match_effect),
+            "query"                 => (&mut self.query_fg,          &mut self.query_effect),
+            "query_bg"              => (&mut self.query_bg,          &mut self.query_effect),
+            "spinner"               => (&mut self.spinner,           &mut self.spinner_effect),
+            "info"                  => (&mut self.info,              &mut self.info_effect),
+            "prompt"                => (&mut self.prompt,            &mut self.prompt_effect),
+            "cursor" | "pointer"    => (&mut self.cursor,            &mut self.cursor_effect),
+            "selected" | "marker"   => (&mut self.selected,          &mut self.selected_effect),
+            "header"                => (&mut self.header,            &mut self.header_effect),
+            "border"                => (&mut self.border,            &mut self.border_effect),
+            "label"                 => (&mut self.label,             &mut self.label_effect),
+            "scrollbar"             => (&mut self.scrollbar,         &mut self.scrollbar_effect),
+            "preview_scrollbar"     => (&mut self.preview_scrollbar, &mut self.preview_scrollbar_effect),
+            "annotation"            => (&mut self.annotation,        &mut self.annotation_effect),
+            "ghost"                 => (&mut self.ghost,             &mut self.ghost_effect),
+            "hl2" | "matched2"      => (&mut self.matched_terms[0],  &mut self.matched_terms_effect[0]),
+            "hl3" | "matched3"      => (&mut self.matched_terms[1],  &mut self.matched_terms_effect[1]),
+            "hl4" | "matched4"      => (&mut self.matched_terms[2],  &mut self.matched_terms_effect[2]),
+            "hl5" | "matched5"      => (&mut self.matched_terms[3],  &mut self.matched_terms_effect[3]),
+            "hl6" | "matched6"      => (&mut self.matched_terms[4],  &mut self.matched_terms_effect[4]),
+            _ => return None,
+        };
+        Some(element)
     }
 
     pub fn normal(&self) -> Attr {
@@ -265,7 +445,7 @@ impl ColorTheme {
         Attr {
             fg: self.spinner,
             bg: self.bg,
-            effect: Effect::BOLD,
+            effect: self.spinner_effect,
         }
     }
 
@@ -273,7 +453,7 @@ impl ColorTheme {
         Attr {
             fg: self.info,
             bg: self.bg,
-            effect: Effect::empty(),
+            effect: self.info_effect,
         }
     }
 
@@ -281,7 +461,7 @@ impl ColorTheme {
         Attr {
             fg: self.prompt,
             bg: self.bg,
-            effect: Effect::empty(),
+            effect: self.prompt_effect,
         }
     }
 
@@ -289,7 +469,7 @@ impl ColorTheme {
         Attr {
             fg: self.cursor,
             bg: self.current_bg,
-            effect: Effect::empty(),
+            effect: self.cursor_effect,
         }
     }
 
@@ -297,7 +477,7 @@ impl ColorTheme {
         Attr {
             fg: self.selected,
             bg: self.current_bg,
-            effect: Effect::empty(),
+            effect: self.selected_effect,
         }
     }
 
@@ -305,7 +485,7 @@ impl ColorTheme {
         Attr {
             fg: self.header,
             bg: self.bg,
-            effect: Effect::empty(),
+            effect: self.header_effect,
         }
     }
 
@@ -313,7 +493,275 @@ impl ColorTheme {
         Attr {
             fg: self.border,
             bg: self.bg,
-            effect: Effect::empty(),
+            effect: self.border_effect,
         }
     }
+
+    pub fn label(&self) -> Attr {
+        Attr {
+            fg: self.label,
+            bg: self.bg,
+            effect: self.label_effect,
+        }
+    }
+
+    pub fn scrollbar(&self) -> Attr {
+        Attr {
+            fg: self.scrollbar,
+            bg: self.bg,
+            effect: self.scrollbar_effect,
+        }
+    }
+
+    pub fn preview_scrollbar(&self) -> Attr {
+        Attr {
+            fg: self.preview_scrollbar,
+            bg: self.bg,
+            effect: self.preview_scrollbar_effect,
+        }
+    }
+
+    /// the highlights of the query terms, the first term takes `highlight` (i.e. `matched()` or
+    /// `current_match()`), the others take their own colors over it
+    pub fn term_highlights(&self, highlight: Attr) -> [Attr; NUM_TERM_COLORS + 1] {
+        let mut highlights = [highlight; NUM_TERM_COLORS + 1];
+        let terms = self.matched_terms.iter().zip(self.matched_terms_effect.iter());
+        for (attr, (&color, &effect)) in highlights[1..].iter_mut().zip(terms) {
+            attr.fg = if color == Color::Default { highlight.fg } else { color };
+            attr.effect |= effect;
+        }
+        highlights
+    }
+
+    pub fn annotation(&self) -> Attr {
+        Attr {
+            fg: self.annotation,
+            bg: self.bg,
+            effect: self.annotation_effect,
+        }
+    }
+
+    pub fn ghost(&self) -> Attr {
+        Attr {
+            fg: self.ghost,
+            bg: self.bg,
+            effect: self.ghost_effect,
+        }
+    }
+}
+
+fn is_light(r: u8, g: u8, b: u8) -> bool {
+    // the perceived brightness, ITU-R BT.601
+    let luma = 299 * r as u32 + 587 * g as u32 + 114 * b as u32;
+    luma > 128 * 1000
+}
+
+/// Query the background color of the terminal (OSC 11), `None` if it doesn't answer in time
+#[cfg(unix)]
+fn query_background() -> Option<(u8, u8, u8)> {
+    use std::fs::OpenOptions;
+    use std::io::{Read, Write};
+    use std::os::unix::io::AsRawFd;
+    use std::time::Instant;
+
+    let mut tty = OpenOptions::new().read(true).write(true).open("/dev/tty").ok()?;
+    let fd = tty.as_raw_fd();
+
+    // no echo nor line buffering, so that the answer could be read as is
+    let mut termios: libc::termios = unsafe { std::mem::zeroed() };
+    if unsafe { libc::tcgetattr(fd, &mut termios) } != 0 {
+        return None;
+    }
+    let original = termios;
+    termios.c_lflag &= !(libc::ICANON | libc::ECHO);
+    termios.c_cc[libc::VMIN] = 0;
+    termios.c_cc[libc::VTIME] = 0;
+    if unsafe { libc::tcsetattr(fd, libc::TCSANOW, &termios) } != 0 {
+        return None;
+    }
+
+    let mut answer = Vec::new();
+    if tty.write_all(b"\x1b]11;?\x07").and_then(|_| tty.flush()).is_ok() {
+        let deadline = Instant::now() + BACKGROUND_QUERY_TIMEOUT;
+        let mut buf = [0u8; 64];
+        loop {
+            let timeout = deadline.saturating_duration_since(Instant::now());
+            let mut pollfd = libc::pollfd {
+                fd,
+                events: libc::POLLIN,
+                revents: 0,
+            };
+            if timeout.is_zero() || unsafe { libc::poll(&mut pollfd, 1, timeout.as_millis() as libc::c_int) } <= 0 {
+                break;
+            }
+
+            match tty.read(&mut buf) {
+                Ok(n) if n > 0 => answer.extend_from_slice(&buf[..n]),
+                _ => break,
+            }
+            // terminated by BEL or ST
+            if answer.ends_with(b"\x07") || answer.ends_with(b"\x1b\\") {
+                break;
+            }
+        }
+    }
+
+    // drop a reply arriving after the timeout, or it would be read as typed keys
+    unsafe {
+        libc::tcflush(fd, libc::TCIFLUSH);
+        libc::tcsetattr(fd, libc::TCSANOW, &original);
+    }
+    parse_background(&String::from_utf8_lossy(&answer))
+}
+
+#[cfg(not(unix))]
+fn query_background() -> Option<(u8, u8, u8)> {
+    None
+}
+
+/// parse the answer of OSC 11, e.g. `\x1b]11;rgb:ffff/ffff/dddd\x07`
+fn parse_background(answer: &str) -> Option<(u8, u8, u8)> {
+    let rgb = &answer[answer.find("rgb:")? + 4..];
+    let rgb = rgb.trim_end_matches(['\x07', '\x1b', '\\']);
+    let mut components = rgb.split('/').map(|component| {
+        // 1 to 4 hex digits, scaled to 8 bits
+        let value = u32::from_str_radix(component, 16).ok()?;
+        let max = match component.len() {
+            len @ 1..=4 => (1u32 << (4 * len)) - 1,
+            _ => return None,
+        };
+        Some((value * 255 / max) as u8)
+    });
+
+    let r = components.next()??;
+    let g = components.next()??;
+    let b = components.next()??;
+    Some((r, g, b))
+}
+
+/// `#rrggbb`, an ANSI color number, a named ANSI color or `-1` for the default of the terminal
+#[rustfmt::skip]
+fn parse_color(color: &str) -> Option<Color> {
+    if let Some(hex) = color.strip_prefix('#') {
+        if hex.len() != 6 || !hex.is_ascii() {
+            return None;
+        }
+        let r = u8::from_str_radix(&hex[0..2], 16).ok()?;
+        let g = u8::from_str_radix(&hex[2..4], 16).ok()?;
+        let b = u8::from_str_radix(&hex[4..6], 16).ok()?;
+        return Some(Color::Rgb(r, g, b));
+    }
+
+    let color = match color {
+        "-1" | "default"   => Color::Default,
+        "black"            => Color::BLACK,
+        "red"              => Color::RED,
+        "green"            => Color::GREEN,
+        "yellow"           => Color::YELLOW,
+        "blue"             => Color::BLUE,
+        "magenta"          => Color::MAGENTA,
+        "cyan"             => Color::CYAN,
+        "white"            => Color::WHITE,
+        "bright-black"     => Color::LIGHT_BLACK,
+        "bright-red"       => Color::LIGHT_RED,
+        "bright-green"     => Color::LIGHT_GREEN,
+        "bright-yellow"    => Color::LIGHT_YELLOW,
+        "bright-blue"      => Color::LIGHT_BLUE,
+        "bright-magenta"   => Color::LIGHT_MAGENTA,
+        "bright-cyan"      => Color::LIGHT_CYAN,
+        "bright-white"     => Color::LIGHT_WHITE,
+        _ => Color::AnsiValue(color.parse::<u8>().ok()?),
+    };
+    Some(color)
+}
+
+#[rustfmt::skip]
+fn parse_effect(effect: &str) -> Option<Effect> {
+    let effect = match effect {
+        "regular"          => Effect::empty(),
+        "bold"             => Effect::BOLD,
+        "dim"              => Effect::DIM,
+        "underline"        => Effect::UNDERLINE,
+        "blink"            => Effect::BLINK,
+        "reverse"          => Effect::REVERSE,
+        _ => return None,
+    };
+    Some(effect)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let theme = ColorTheme::parse("16,hl:#ff0000:bold:underline,bg+:-1,header:bright-blue").unwrap();
+        assert_eq!(
+            theme.matched(),
+            Attr {
+                fg: Color::Rgb(255, 0, 0),
+                bg: Color::BLACK,
+                effect: Effect::BOLD | Effect::UNDERLINE,
+            }
+        );
+        assert_eq!(theme.current_bg, Color::Default);
+        assert_eq!(theme.header().fg, Color::LIGHT_BLUE);
+
+        let theme = ColorTheme::parse("spinner:regular,info:reverse:108").unwrap();
+        assert_eq!(theme.spinner().effect, Effect::empty());
+        assert_eq!(
+            theme.info(),
+            Attr {
+                fg: Color::AnsiValue(108),
+                bg: Color::Default,
+                effect: Effect::REVERSE
+            }
+        );
+
+        assert!(ColorTheme::parse("hl:#ff00").is_err());
+        assert!(ColorTheme::parse("hl:256").is_err());
+        assert!(ColorTheme::parse("hl:red:shiny").is_err());
+        assert!(ColorTheme::parse("highlight:red").is_err());
+        assert!(ColorTheme::parse("solarized").is_err());
+        assert!(ColorTheme::parse("hl:red:italic")
+            .unwrap_err()
+            .contains("not supported"));
+    }
+
+    #[test]
+    fn test_read_spec() {
+        let path = std::env::temp_dir().join(format!("sk-test-read-spec-{}", std::process::id()));
+        std::fs::write(
+            &path,
+            "# solarized light\nlight\n\n  fg:#657b83,bg:#fdf6e3  \n# hl:red\nhl:#268bd2:bold\n",
+        )
+        .unwrap();
+        let spec = ColorTheme::read_spec(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(spec.unwrap(), "light,fg:#657b83,bg:#fdf6e3,hl:#268bd2:bold");
+    }
+
+    #[test]
+    fn test_no_bold() {
+        let options = SkimOptions {
+            color: Some("hl:bold:underline"),
+            no_bold: true,
+            ..SkimOptions::default()
+        };
+        let theme = ColorTheme::init_from_options(&options);
+        assert_eq!(theme.matched().effect, Effect::UNDERLINE);
+        assert_eq!(theme.spinner().effect, Effect::empty());
+    }
+
+    #[test]
+    fn test_parse_background() {
+        assert_eq!(
+            parse_background("\x1b]11;rgb:ffff/ffff/dddd\x07"),
+            Some((255, 255, 221))
+        );
+        assert_eq!(parse_background("\x1b]11;rgb:1c/1c/1c\x1b\\"), Some((28, 28, 28)));
+        assert_eq!(parse_background(""), None);
+        assert!(is_light(255, 255, 221));
+        assert!(!is_light(28, 28, 28));
+    }
 }
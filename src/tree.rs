@@ -0,0 +1,361 @@
+//! Tree display of hierarchical paths (`--tree`)
+//!
+//! The items are grouped by their path segments, split by the separator. Every path prefix is a
+//! node of the tree, the directories which are not items themselves are shown as extra rows.
+//! Directories are collapsed unless expanded by the user, or a query is active.
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Weak};
+
+use tuikit::prelude::*;
+
+use crate::ansi::AnsiString;
+use crate::item::MatchedItem;
+use crate::SkimItem;
+
+pub const DEFAULT_TREE_SEPARATOR: &str = "/";
+const INDENT: &str = "  ";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fold {
+    Leaf,
+    Expanded,
+    Collapsed,
+}
+
+impl Fold {
+    fn glyph(self) -> &'static str {
+        match self {
+            Fold::Leaf => "  ",
+            Fold::Expanded => "▾ ",
+            Fold::Collapsed => "▸ ",
+        }
+    }
+}
+
+/// A visible row of the tree
+#[derive(Clone)]
+pub struct TreeRow {
+    pub item: MatchedItem,
+    pub depth: usize,
+    pub fold: Fold,
+    // the byte offsets of the name (the last segment) and the end of the path in the item text
+    name_start: usize,
+    path_end: usize,
+}
+
+struct Node {
+    item: Option<MatchedItem>,
+    name_start: usize,
+    path_end: usize,
+    path: String,
+    children: Vec<usize>,
+}
+
+pub struct Tree {
+    separator: String,
+    expanded: HashSet<String>,
+    // show all the nodes, e.g. while a query is active
+    expand_all: bool,
+    rows: Vec<TreeRow>,
+    // the directories without an item, kept alive for the rows and the selections
+    dirs: HashMap<String, (u32, Arc<dyn SkimItem>)>,
+}
+
+impl Tree {
+    pub fn new(separator: &str) -> Self {
+        let separator = if separator.is_empty() {
+            DEFAULT_TREE_SEPARATOR
+        } else {
+            separator
+        };
+
+        Self {
+            separator: separator.to_string(),
+            expanded: HashSet::new(),
+            expand_all: false,
+            rows: Vec::new(),
+            dirs: HashMap::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&TreeRow> {
+        self.rows.get(index)
+    }
+
+    pub fn set_expand_all(&mut self, expand_all: bool) {
+        self.expand_all = expand_all;
+    }
+
+    pub fn is_expand_all(&self) -> bool {
+        self.expand_all
+    }
+
+    /// the row of the item
+    pub fn position(&self, item: &MatchedItem) -> Option<usize> {
+        self.rows.iter().position(|row| row.item.item_idx == item.item_idx)
+    }
+
+    /// the path of the directory at the row, `None` for leaves
+    fn dir_path(&self, index: usize) -> Option<String> {
+        let row = self.rows.get(index).filter(|row| row.fold != Fold::Leaf)?;
+        Some(row.item.upgrade_infallible().text()[..row.path_end].to_string())
+    }
+
+    /// the row of the parent directory
+    fn parent(&self, index: usize) -> Option<usize> {
+        let depth = self.rows.get(index)?.depth;
+        (0..index).rev().find(|&idx| self.rows[idx].depth < depth)
+    }
+
+    /// collapse the directory at the row (or the parent of a leaf), return the row of the
+    /// collapsed directory. Changes take effect on the next `build`.
+    pub fn collapse(&mut self, index: usize) -> Option<usize> {
+        if self.expand_all {
+            return None;
+        }
+
+        let index = match self.rows.get(index)?.fold {
+            Fold::Expanded => index,
+            _ => self.parent(index)?,
+        };
+        let path = self.dir_path(index)?;
+        self.expanded.remove(&path);
+        Some(index)
+    }
+
+    /// expand the directory at the row, return false if there is nothing to expand
+    pub fn expand(&mut self, index: usize) -> bool {
+        if self.expand_all || self.rows.get(index).map(|row| row.fold) != Some(Fold::Collapsed) {
+            return false;
+        }
+
+        match self.dir_path(index) {
+            Some(path) => self.expanded.insert(path),
+            None => false,
+        }
+    }
+
+    /// rebuild the rows from the items, the children are in the order of their first items
+    pub fn build(&mut self, items: impl Iterator<Item = MatchedItem>) {
+        let mut nodes: Vec<Node> = Vec::new();
+        let mut index: HashMap<String, usize> = HashMap::new();
+        let mut roots = Vec::new();
+
+        for item in items {
+            let text = item.upgrade_infallible().text().to_string();
+            let path = text.trim_end_matches(self.separator.as_str());
+            let path = if path.is_empty() { text.as_str() } else { path };
+
+            // the nodes of the prefixes, e.g. "a", "a/b" for "a/b/c"
+            let mut parent: Option<usize> = None;
+            let mut name_start = 0;
+            for (sep_start, _) in path.match_indices(self.separator.as_str()) {
+                if sep_start > name_start {
+                    let prefix = &path[..sep_start];
+                    let node = match index.get(prefix) {
+                        Some(&node) => node,
+                        None => {
+                            let node = nodes.len();
+                            nodes.push(Node {
+                                item: None,
+                                name_start,
+                                path_end: sep_start,
+                                path: prefix.to_string(),
+                                children: Vec::new(),
+                            });
+                            index.insert(prefix.to_string(), node);
+                            parent
+                                .map_or(&mut roots, |parent| &mut nodes[parent].children)
+                                .push(node);
+                            node
+                        }
+                    };
+                    parent = Some(node);
+                }
+                name_start = sep_start + self.separator.len();
+            }
+
+            // the node of the item itself, a directory might have been created by its children
+            let node = match index.get(path) {
+                Some(&node) if nodes[node].item.is_none() => node,
+                _ => {
+                    let node = nodes.len();
+                    nodes.push(Node {
+                        item: None,
+                        name_start,
+                        path_end: path.len(),
+                        path: path.to_string(),
+                        children: Vec::new(),
+                    });
+                    index.entry(path.to_string()).or_insert(node);
+                    parent
+                        .map_or(&mut roots, |parent| &mut nodes[parent].children)
+                        .push(node);
+                    node
+                }
+            };
+            nodes[node].item = Some(item);
+        }
+
+        let mut rows = Vec::with_capacity(nodes.len());
+        let mut stack: Vec<(usize, usize)> = roots.iter().rev().map(|&node| (node, 0)).collect();
+        while let Some((node, depth)) = stack.pop() {
+            let Node {
+                item,
+                name_start,
+                path_end,
+                path,
+                children,
+            } = &nodes[node];
+
+            let fold = if children.is_empty() {
+                Fold::Leaf
+            } else if self.expand_all || self.expanded.contains(path) {
+                Fold::Expanded
+            } else {
+                Fold::Collapsed
+            };
+
+            let item = match item {
+                Some(item) => item.clone(),
+                None => self.dir_item(path),
+            };
+
+            rows.push(TreeRow {
+                item,
+                depth,
+                fold,
+                name_start: *name_start,
+                path_end: *path_end,
+            });
+
+            if fold == Fold::Expanded {
+                stack.extend(children.iter().rev().map(|&child| (child, depth + 1)));
+            }
+        }
+
+        self.rows = rows;
+    }
+
+    fn dir_item(&mut self, path: &str) -> MatchedItem {
+        let num_dirs = self.dirs.len() as u32;
+        let (item_idx, item) = self
+            .dirs
+            .entry(path.to_string())
+            .or_insert_with(|| (u32::MAX - num_dirs, Arc::new(path.to_string())));
+
+        MatchedItem {
+            item: Arc::downgrade(item) as Weak<dyn SkimItem>,
+            rank: Default::default(),
+            matched_range: None,
+            item_idx: *item_idx,
+        }
+    }
+
+    /// indent the name of the row by its depth, return the content and the position of every char
+    /// of `content` in it (`None` for the chars of the parent directories)
+    pub fn layout(&self, row: &TreeRow, content: &AnsiString) -> (AnsiString, Vec<Option<usize>>) {
+        let text = content.stripped();
+        let name_start = text.get(..row.name_start).map_or(0, |prefix| prefix.chars().count());
+
+        let mut output: String = INDENT.repeat(row.depth);
+        output.push_str(row.fold.glyph());
+        let prefix_len = output.chars().count();
+
+        let mut fragments: Vec<(Attr, (u32, u32))> = Vec::new();
+        let mut positions = Vec::with_capacity(text.len());
+        for (idx, (ch, attr)) in content.iter().enumerate() {
+            if idx < name_start {
+                positions.push(None);
+                continue;
+            }
+
+            let pos = prefix_len + idx - name_start;
+            positions.push(Some(pos));
+            output.push(ch);
+            if attr != Attr::default() {
+                fragments.push((attr, (pos as u32, pos as u32 + 1)));
+            }
+        }
+
+        (AnsiString::new_string(output, fragments), positions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows(tree: &Tree) -> Vec<(String, usize, Fold)> {
+        tree.rows
+            .iter()
+            .map(|row| (row.item.upgrade_infallible().text().to_string(), row.depth, row.fold))
+            .collect()
+    }
+
+    #[test]
+    fn test_build() {
+        let items: Vec<Arc<dyn SkimItem>> = ["a/b/c", "a/d", "e", "a/b"]
+            .iter()
+            .map(|&text| Arc::new(text) as Arc<dyn SkimItem>)
+            .collect();
+        let matched = || {
+            items.iter().enumerate().map(|(idx, item)| MatchedItem {
+                item: Arc::downgrade(item),
+                rank: Default::default(),
+                matched_range: None,
+                item_idx: idx as u32,
+            })
+        };
+
+        let mut tree = Tree::new("/");
+        tree.build(matched());
+        assert_eq!(
+            rows(&tree),
+            vec![("a".to_string(), 0, Fold::Collapsed), ("e".to_string(), 0, Fold::Leaf)]
+        );
+
+        assert!(tree.expand(0));
+        tree.build(matched());
+        assert_eq!(
+            rows(&tree),
+            vec![
+                ("a".to_string(), 0, Fold::Expanded),
+                ("a/b".to_string(), 1, Fold::Collapsed),
+                ("a/d".to_string(), 1, Fold::Leaf),
+                ("e".to_string(), 0, Fold::Leaf),
+            ]
+        );
+        // the item "a/b" takes the place of the directory
+        assert_eq!(tree.get(1).unwrap().item.item_idx, 3);
+
+        // collapsing a leaf collapses its parent
+        assert_eq!(tree.collapse(2), Some(0));
+        tree.set_expand_all(true);
+        tree.build(matched());
+        assert_eq!(tree.len(), 5);
+        assert_eq!(tree.get(2).unwrap().depth, 2);
+    }
+
+    #[test]
+    fn test_layout() {
+        let item: Arc<dyn SkimItem> = Arc::new("a/b/c");
+        let mut tree = Tree::new("/");
+        tree.set_expand_all(true);
+        tree.build(std::iter::once(MatchedItem {
+            item: Arc::downgrade(&item),
+            rank: Default::default(),
+            matched_range: None,
+            item_idx: 0,
+        }));
+
+        let row = tree.get(2).unwrap();
+        let (content, positions) = tree.layout(row, &AnsiString::from("a/b/c"));
+        assert_eq!(content.stripped(), "      c");
+        assert_eq!(positions, vec![None, None, None, None, Some(6)]);
+    }
+}
@@ -4,6 +4,7 @@ use std::prelude::v1::*;
 
 use regex::{Captures, Regex};
 use std::sync::LazyLock;
+use std::time::Duration;
 use tuikit::prelude::*;
 use unicode_width::UnicodeWidthChar;
 
@@ -216,6 +217,9 @@ pub fn accumulate_text_width(text: &str, tabstop: usize) -> Vec<usize> {
 ///               shift ->|               |
 /// ```
 ///
+/// `hscroll_off` is the number of columns to keep visible around the match when the line is shifted,
+/// the match is centered if it could not be kept on either side.
+///
 /// return (left_shift, full_print_width)
 pub fn reshape_string(
     text: &str,
@@ -223,6 +227,7 @@ pub fn reshape_string(
     match_start: usize,
     match_end: usize,
     tabstop: usize,
+    hscroll_off: usize,
 ) -> (usize, usize) {
     if text.is_empty() {
         return (0, 0);
@@ -247,17 +252,48 @@ pub fn reshape_string(
     };
     let w3 = acc_width[acc_width.len() - 1] - w1 - w2;
 
-    if (w1 > w3 && w2 + w3 <= container_width) || (w3 <= 2) {
+    // the context could not be wider than what is left around the match
+    let off = min(hscroll_off, container_width.saturating_sub(w2) / 2);
+
+    if w2 + w3 + off <= container_width && (w1 > w3 || w3 <= off) {
         // right-fixed
-        //(right_fixed(&acc_width, container_width), full_width)
         (full_width - container_width, full_width)
-    } else if w1 <= w3 && w1 + w2 <= container_width {
+    } else if w1 <= w3 && w1 + w2 + off <= container_width {
         // left-fixed
         (0, full_width)
+    } else if w2 > container_width {
+        // the match could not be shown as a whole, show its start
+        (min(w1, full_width - container_width), full_width)
     } else {
-        // left-right
-        (acc_width[match_end] - container_width + 2, full_width)
+        // left-right, center the match
+        let shift = w1.saturating_sub((container_width - w2) / 2);
+        (min(shift, full_width - container_width), full_width)
+    }
+}
+
+/// the char range `[start, end)` of the densest cluster of the (sorted) `matched_indices`, i.e. the
+/// one with the most matched chars within `window` columns. Earlier clusters win ties.
+pub fn densest_match_cluster(text: &str, matched_indices: &[usize], window: usize, tabstop: usize) -> (usize, usize) {
+    if matched_indices.is_empty() {
+        return (0, 0);
+    }
+
+    let acc_width = accumulate_text_width(text, tabstop);
+    let width_till = |idx: usize| acc_width.get(idx).copied().unwrap_or(0);
+    let width_before = |idx: usize| if idx == 0 { 0 } else { width_till(idx - 1) };
+
+    let mut best = (0, 0);
+    let mut left = 0;
+    for right in 0..matched_indices.len() {
+        while left < right && width_till(matched_indices[right]) - width_before(matched_indices[left]) > window {
+            left += 1;
+        }
+        if right - left > best.1 - best.0 {
+            best = (left, right);
+        }
     }
+
+    (matched_indices[best.0], matched_indices[best.1] + 1)
 }
 
 /// margin option string -> Size
@@ -402,6 +438,24 @@ pub fn atoi<T: std::str::FromStr>(string: &str) -> Option<T> {
     RE_NUMBER.find(string).and_then(|mat| mat.as_str().parse::<T>().ok())
 }
 
+/// Parse a duration, e.g. `2s`, `500ms`, `1.5` (in seconds). `None` if invalid or not positive
+pub fn parse_duration(duration: &str) -> Option<Duration> {
+    let duration = duration.trim();
+    let (number, scale) = if let Some(millis) = duration.strip_suffix("ms") {
+        (millis, 0.001)
+    } else if let Some(secs) = duration.strip_suffix('s') {
+        (secs, 1.0)
+    } else {
+        (duration, 1.0)
+    };
+    number
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|number| number.is_finite() && *number > 0.0)
+        .map(|number| Duration::from_secs_f64(number * scale))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -417,11 +471,33 @@ mod tests {
     #[test]
     fn test_reshape_string() {
         // no match, left fixed to 0
-        assert_eq!(reshape_string("abc", 10, 0, 0, 8), (0, 3));
-        assert_eq!(reshape_string("a\tbc", 8, 0, 0, 8), (0, 10));
-        assert_eq!(reshape_string("a\tb\tc", 10, 0, 0, 8), (0, 17));
-        assert_eq!(reshape_string("a\t中b\tc", 8, 0, 0, 8), (0, 17));
-        assert_eq!(reshape_string("a\t中b\tc012345", 8, 0, 0, 8), (0, 23));
+        assert_eq!(reshape_string("abc", 10, 0, 0, 8, 0), (0, 3));
+        assert_eq!(reshape_string("a\tbc", 8, 0, 0, 8, 0), (0, 10));
+        assert_eq!(reshape_string("a\tb\tc", 10, 0, 0, 8, 0), (0, 17));
+        assert_eq!(reshape_string("a\t中b\tc", 8, 0, 0, 8, 0), (0, 17));
+        assert_eq!(reshape_string("a\t中b\tc012345", 8, 0, 0, 8, 0), (0, 23));
+
+        // keep the context after the match
+        assert_eq!(reshape_string("0123456789abcdefghij", 10, 6, 8, 8, 0), (0, 20));
+        assert_eq!(reshape_string("0123456789abcdefghij", 10, 6, 8, 8, 3), (3, 20));
+        // keep the context before the match
+        assert_eq!(reshape_string("0123456789abcdefghij", 10, 17, 18, 8, 3), (10, 20));
+        // center the match otherwise
+        assert_eq!(
+            reshape_string("0123456789abcdefghijklmnopqrst", 10, 14, 16, 8, 10),
+            (11, 30)
+        );
+    }
+
+    #[test]
+    fn test_densest_match_cluster() {
+        assert_eq!(densest_match_cluster("abc", &[], 10, 8), (0, 0));
+        // all the matches fit in the window
+        assert_eq!(densest_match_cluster("a-b-c-----", &[0, 2, 4], 10, 8), (0, 5));
+        // the scattered first match is skipped
+        assert_eq!(densest_match_cluster("a---------bcd", &[0, 10, 11, 12], 5, 8), (10, 13));
+        // ties go to the earlier cluster
+        assert_eq!(densest_match_cluster("ab--------cd", &[0, 1, 10, 11], 5, 8), (0, 2));
     }
 
     #[test]
@@ -483,4 +559,16 @@ mod tests {
         assert_eq!(None, atoi::<i32>("8589934592"));
         assert_eq!(Some(123), atoi::<i32>("+'123'"));
     }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(Some(Duration::from_secs(2)), parse_duration("2s"));
+        assert_eq!(Some(Duration::from_secs(2)), parse_duration("2"));
+        assert_eq!(Some(Duration::from_millis(500)), parse_duration("500ms"));
+        assert_eq!(Some(Duration::from_millis(1500)), parse_duration("1.5s"));
+        assert_eq!(None, parse_duration("0"));
+        assert_eq!(None, parse_duration("-1s"));
+        assert_eq!(None, parse_duration("2m"));
+        assert_eq!(None, parse_duration(""));
+    }
 }
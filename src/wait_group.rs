@@ -0,0 +1,129 @@
+//! WaitGroup implemented using Mutex and Condvar
+//! Tracks the number of running components (threads) and lets other threads
+//! block until all of them are stopped, instead of spinning on an atomic.
+//!
+//! 1. `add(n)` before spawning the components, so that the count is never observed too low.
+//! 2. `done()` when a component terminates, waking up the waiters if none is left.
+//! 3. `wait()` / `wait_timeout()` block until the count drops to zero.
+use std::sync::{Condvar, Mutex, MutexGuard};
+use std::time::Duration;
+
+#[derive(Debug, Default)]
+pub struct WaitGroup {
+    count: Mutex<usize>,
+    cond: Condvar,
+}
+
+impl WaitGroup {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, usize> {
+        // a panicking component should not prevent others from being tracked
+        self.count.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// register `n` new running components
+    pub fn add(&self, n: usize) {
+        *self.lock() += n;
+    }
+
+    /// mark one component as stopped
+    pub fn done(&self) {
+        let mut count = self.lock();
+        *count = count.saturating_sub(1);
+        if *count == 0 {
+            self.cond.notify_all();
+        }
+    }
+
+    /// number of components that are still running
+    pub fn count(&self) -> usize {
+        *self.lock()
+    }
+
+    pub fn all_stopped(&self) -> bool {
+        self.count() == 0
+    }
+
+    /// block until all components are stopped
+    pub fn wait(&self) {
+        let count = self.lock();
+        let _count = self
+            .cond
+            .wait_while(count, |count| *count != 0)
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+    }
+
+    /// block until all components are stopped or the timeout elapsed.
+    /// return `true` if all components are stopped
+    pub fn wait_timeout(&self, timeout: Duration) -> bool {
+        let count = self.lock();
+        let (count, _) = self
+            .cond
+            .wait_timeout_while(count, timeout, |count| *count != 0)
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        *count == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn empty_group_does_not_block() {
+        let wg = WaitGroup::new();
+        assert!(wg.all_stopped());
+        assert!(wg.wait_timeout(Duration::from_millis(0)));
+        wg.wait();
+    }
+
+    #[test]
+    fn wait_timeout_observes_running_components() {
+        let wg = WaitGroup::new();
+        wg.add(2);
+        assert_eq!(wg.count(), 2);
+        assert!(!wg.wait_timeout(Duration::from_millis(10)));
+
+        wg.done();
+        assert!(!wg.wait_timeout(Duration::from_millis(10)));
+
+        wg.done();
+        assert!(wg.wait_timeout(Duration::from_millis(10)));
+    }
+
+    #[test]
+    fn wait_is_woken_up_by_other_threads() {
+        const K: usize = 8;
+        let wg = Arc::new(WaitGroup::new());
+        wg.add(K);
+
+        let handles: Vec<_> = (0..K)
+            .map(|_| {
+                let wg = wg.clone();
+                thread::spawn(move || {
+                    thread::sleep(Duration::from_millis(5));
+                    wg.done();
+                })
+            })
+            .collect();
+
+        wg.wait();
+        assert!(wg.all_stopped());
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+    }
+
+    #[test]
+    fn done_saturates_at_zero() {
+        let wg = WaitGroup::new();
+        wg.done();
+        assert_eq!(wg.count(), 0);
+    }
+}